@@ -1,30 +1,543 @@
-use std::path::PathBuf;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::Duration;
 
-use eyre::Result;
-use serde::Deserialize;
+use crossterm::event::KeyCode;
+use eyre::{Context, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use crate::project::ProjectOpener;
-use crate::tui::ColorConfig;
+use crate::project::{
+    abbreviate_home, scan_finds, HealthConfig, Project, ProjectOpener, SortKey, SpawnRetry,
+};
+use crate::tui::{
+    ColorConfig, ColumnConfig, KeyMap, LeftAction, NameTruncation, PaneLayout, QuitKey,
+};
 
-#[derive(Debug, Deserialize, Default)]
+/// Readme candidates used when `readme_files` isn't set
+const DEFAULT_README_FILES: &[&str] = &[
+    "README.md",
+    "README.markdown",
+    "README.rst",
+    "README.adoc",
+    "README.txt",
+    "README",
+];
+
+/// Places projects are commonly kept, scanned if they exist when `auto_discover_roots` is set
+const COMMON_PROJECT_DIRS: &[&str] = &[
+    "~/src",
+    "~/code",
+    "~/projects",
+    "~/dev",
+    "~/git",
+    "~/go/src",
+];
+
+/// Stands in as the project directory of the projects listed in `projects`
+static LISTED_PROJECTS: LazyLock<ProjectDir> = LazyLock::new(|| ProjectDir {
+    path: "projects".to_string(),
+    label: Some("Listed projects".to_string()),
+    opener: None,
+});
+
+/// A directory whose subdirectories are projects
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(from = "ProjectDirDef")]
+pub struct ProjectDir {
+    pub path: String,
+    /// Friendly name shown instead of the path
+    pub label: Option<String>,
+    /// Opener for projects in this directory instead of the global `opener`
+    pub opener: Option<ProjectOpener>,
+}
+
+/// `project_dirs` entries can be a bare path or a table with a label
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ProjectDirDef {
+    Path(String),
+    Table(ProjectDirTable),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProjectDirTable {
+    path: String,
+    #[serde(default)]
+    label: Option<String>,
+    #[serde(default)]
+    opener: Option<ProjectOpener>,
+}
+
+impl From<ProjectDirDef> for ProjectDir {
+    fn from(def: ProjectDirDef) -> Self {
+        match def {
+            ProjectDirDef::Path(path) => ProjectDir::new(path),
+            ProjectDirDef::Table(ProjectDirTable {
+                path,
+                label,
+                opener,
+            }) => ProjectDir {
+                path,
+                label,
+                opener,
+            },
+        }
+    }
+}
+
+impl ProjectDir {
+    pub fn new(path: impl Into<String>) -> Self {
+        ProjectDir {
+            path: path.into(),
+            label: None,
+            opener: None,
+        }
+    }
+
+    /// Path with `~` expanded to the home directory
+    pub fn expanded_path(&self) -> PathBuf {
+        PathBuf::from(shellexpand::tilde(&self.path).into_owned())
+    }
+
+    /// Label if one was configured otherwise the path, with the home directory shown as
+    /// `~` unless `full_paths` is set
+    pub fn display_name(&self, full_paths: bool) -> Cow<'_, str> {
+        match &self.label {
+            Some(label) => Cow::Borrowed(label),
+            None if full_paths => Cow::Owned(self.expanded_path().display().to_string()),
+            None => Cow::Owned(abbreviate_home(&self.expanded_path())),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
-    pub project_dirs: Vec<String>,
+    #[serde(default)]
+    pub project_dirs: Vec<ProjectDir>,
+    /// Project paths added whether or not they are in one of the `project_dirs`
+    #[serde(default)]
+    pub projects: Vec<String>,
+    /// Scan common project directories that exist when neither `project_dirs` nor
+    /// `projects` are set
+    #[serde(default)]
+    pub auto_discover_roots: bool,
+    /// Whether `project_dirs` were found by `auto_discover_roots` instead of configured
+    #[serde(skip)]
+    pub roots_discovered: bool,
     #[serde(default)]
     pub colors: ColorConfig,
     #[serde(default)]
     pub opener: ProjectOpener,
+    /// Times to retry launching an opener that fails to start
+    #[serde(default)]
+    pub open_retries: u32,
+    /// Milliseconds before the first retry of an opener, doubling for each retry after
+    #[serde(default)]
+    pub open_retry_backoff: Option<u64>,
+    #[serde(default)]
+    pub sort: SortKey,
+    /// Show project logos in the info pane on terminals that support images
+    #[serde(default)]
+    pub image_preview: bool,
+    #[serde(default)]
+    pub left_action: LeftAction,
+    /// Which part of a project name too long for its column is cut
+    #[serde(default)]
+    pub name_truncation: NameTruncation,
+    /// Moving down from the last project selects the first and up from the first the last
+    #[serde(default)]
+    pub wrap_navigation: Option<bool>,
+    /// Command run with the selected project's path appended whenever the selection changes
+    #[serde(default)]
+    pub on_select_command: Option<Vec<String>>,
+    /// Terminal emulator launched in the selected project, with placeholders in its
+    /// arguments replaced as in command openers
+    #[serde(default)]
+    pub terminal_command: Option<Vec<String>>,
+    /// Command run in the background once porgi starts, alongside the first scan
+    #[serde(default)]
+    pub startup_command: Option<Vec<String>>,
+    /// Command run in a project just created from a template, e.g. `git init`
+    #[serde(default)]
+    pub template_command: Option<Vec<String>>,
+    /// List the members of Cargo, npm/yarn and pnpm workspaces under their workspace
+    #[serde(default)]
+    pub expand_workspaces: bool,
+    /// Levels below each of the `project_dirs` projects are found at, git repositories
+    /// above that level are projects as well
+    #[serde(default)]
+    pub scan_depth: Option<usize>,
+    /// Seconds without input after which porgi exits
+    #[serde(default)]
+    pub idle_timeout: Option<u64>,
+    /// Minutes between automatic rescans of the project directories
+    #[serde(default)]
+    pub auto_refresh_interval: Option<u64>,
+    /// Watch the project directories, rereading projects whose files change and adding
+    /// new ones as they are created. Off by default.
+    #[serde(default)]
+    pub watch: Option<bool>,
+    /// Extra marker file names identifying a kind of project, e.g. `"dvc.yaml" = "DataScience"`
+    #[serde(default)]
+    pub project_markers: HashMap<String, String>,
+    /// Layout used at startup, Tab switches between them
+    #[serde(default)]
+    pub layout: PaneLayout,
+    /// Files skipped when walking projects
+    #[serde(default)]
+    pub walk: WalkConfig,
+    /// Stop walking a project after this many files, for huge trees
+    #[serde(default)]
+    pub max_walk_files: Option<usize>,
+    /// Sections of the info pane in the order they are shown
+    #[serde(default)]
+    pub info_sections: Vec<String>,
+    /// Columns shown in the project list
+    #[serde(default)]
+    pub columns: ColumnConfig,
+    /// Weights of the signals making up each project's health score
+    #[serde(default)]
+    pub health: HealthConfig,
+    /// Show paths in full rather than with the home directory as `~`
+    #[serde(default)]
+    pub full_paths: bool,
+    /// Alternate the background of project rows using `even_row_color` and `odd_row_color`
+    #[serde(default)]
+    pub striped_rows: bool,
+    /// Quit with Enter, reporting the selected project as the terminal's working directory
+    #[serde(default)]
+    pub cd_on_exit: bool,
+    /// Issue tracker URL templates by forge host, `{url}` is replaced with the repository URL
+    #[serde(default)]
+    pub issue_trackers: HashMap<String, String>,
+    /// Readme file names relative to the project root, the first one found is shown
+    #[serde(default)]
+    pub readme_files: Vec<String>,
+    /// Only match `readme_files` with the exact same case
+    #[serde(default)]
+    pub readme_case_sensitive: bool,
+    /// Read readmes from disk when a project is selected instead of keeping them all in memory
+    #[serde(default)]
+    pub lazy_readme: bool,
+    /// Show markdown readmes styled rather than as raw text
+    #[serde(default)]
+    pub render_markdown: Option<bool>,
+    /// Use the time of the last commit as the modified time of git repositories
+    #[serde(default)]
+    pub use_git_time: bool,
+    /// Percentage of the width given to the project list in the split layout
+    #[serde(default)]
+    pub split_ratio: Option<u16>,
+    /// Key or key sequence that quits, when it isn't Esc then Esc clears the filter
+    #[serde(default)]
+    pub quit_key: QuitKey,
+    /// Ask before quitting
+    #[serde(default)]
+    pub confirm_quit: bool,
+    /// Keys for quitting, opening and moving around the project list
+    #[serde(default)]
+    pub keybindings: KeyMap,
+    /// Named sets of settings chosen with `--profile`, `default` is used without it
+    #[serde(default)]
+    pub profiles: HashMap<String, ProfileConfig>,
+    /// File the config was loaded from
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+    /// Milliseconds to wait for the second key of sequences like `gg`
+    #[serde(default)]
+    pub key_sequence_timeout: Option<u64>,
+    /// Columns below which a message asking to resize is shown instead of the layout
+    #[serde(default)]
+    pub min_width: Option<u16>,
+    /// Rows below which a message asking to resize is shown instead of the layout
+    #[serde(default)]
+    pub min_height: Option<u16>,
+}
+
+/// Which files are skipped when walking a project to count its files, size and last
+/// modified time. Everything is skipped by default, matching `git status`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WalkConfig {
+    /// Skip files matched by `.gitignore` and `.git/info/exclude`
+    pub respect_gitignore: bool,
+    /// Skip files matched by the global gitignore, `core.excludesFile`
+    pub respect_git_global: bool,
+    /// Skip files matched by `.ignore` files, as used by ripgrep and fd
+    pub respect_ignore_files: bool,
+    /// Also use ignore files from the directories above the project
+    pub respect_parent_ignores: bool,
+    /// Skip hidden files and directories
+    pub skip_hidden: bool,
+}
+
+impl Default for WalkConfig {
+    fn default() -> Self {
+        Self {
+            respect_gitignore: true,
+            respect_git_global: true,
+            respect_ignore_files: true,
+            respect_parent_ignores: true,
+            skip_hidden: true,
+        }
+    }
+}
+
+/// Per-project settings read from a `.porgi.toml` in the project's root
+#[derive(Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// Environment variables set for any opener launched for the project.
+    /// These take precedence over the opener's own `env`.
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Opener for this project instead of its root's or the global `opener`
+    #[serde(default)]
+    pub opener: Option<ProjectOpener>,
+}
+
+impl ProjectConfig {
+    pub const FILE_NAME: &'static str = ".porgi.toml";
+
+    /// Load the project's config if it has one
+    pub fn load(project_path: &Path) -> Result<Option<Self>> {
+        let path = project_path.join(Self::FILE_NAME);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let config = std::fs::read_to_string(&path)?;
+        parse_toml(&config, &path).map(Some)
+    }
+}
+
+/// Settings a profile replaces the top-level ones with, those left out keep the
+/// top-level value
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileConfig {
+    #[serde(default)]
+    pub project_dirs: Option<Vec<ProjectDir>>,
+    #[serde(default)]
+    pub projects: Option<Vec<String>>,
+    #[serde(default)]
+    pub opener: Option<ProjectOpener>,
+    #[serde(default)]
+    pub colors: Option<ColorConfig>,
+}
+
+/// Narrowest the project list or info pane can be made, in percent
+const MIN_SPLIT_RATIO: u16 = 20;
+
+/// Limit a project list width so neither pane disappears
+pub fn clamp_split_ratio(ratio: u16) -> u16 {
+    ratio.clamp(MIN_SPLIT_RATIO, 100 - MIN_SPLIT_RATIO)
+}
+
+/// Milliseconds before retrying an opener when not configured
+const DEFAULT_OPEN_RETRY_BACKOFF: u64 = 500;
+
+/// How long to wait for the second key of a sequence when `key_sequence_timeout` isn't set
+const DEFAULT_KEY_SEQUENCE_TIMEOUT: u64 = 500;
+
+/// Smallest terminal the layout is usable in when not configured, in columns and rows
+const DEFAULT_MIN_TERMINAL_SIZE: (u16, u16) = (40, 10);
+
+/// Parse the contents of the config file at `path`, errors name the line and column of
+/// the offending key
+fn parse_toml<T: DeserializeOwned>(contents: &str, path: &Path) -> Result<T> {
+    toml::from_str(contents).map_err(|e| {
+        let location = match e.span() {
+            Some(span) => {
+                let before = &contents[..span.start];
+                let line = before.matches('\n').count() + 1;
+                let column = before
+                    .rsplit('\n')
+                    .next()
+                    .unwrap_or_default()
+                    .chars()
+                    .count()
+                    + 1;
+                format!("{}:{}:{}", path.display(), line, column)
+            }
+            None => path.display().to_string(),
+        };
+
+        eyre::eyre!("{} in {}", e.message(), location)
+    })
 }
 
-fn must_exist(p: &PathBuf) -> Option<&PathBuf> {
-    if p.exists() {
-        Some(p)
-    } else {
-        None
+/// Read a config file, explaining what is wrong with paths that exist but can't be read
+fn read_config_file(path: &Path) -> Result<String> {
+    if path.is_dir() {
+        return Err(eyre::eyre!(
+            "{} is a directory, expected a config file",
+            path.display()
+        ));
     }
+
+    std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("Could not read config file {}", path.display()))
 }
 
 impl Config {
+    /// `path` as shown in the UI, see `full_paths`
+    pub fn display_path(&self, path: &Path) -> String {
+        if self.full_paths {
+            path.display().to_string()
+        } else {
+            abbreviate_home(path)
+        }
+    }
+
+    /// Root directory a project was found in
+    pub fn root(&self, project: &Project) -> &ProjectDir {
+        self.project_dirs
+            .get(project.root)
+            .unwrap_or(&LISTED_PROJECTS)
+    }
+
+    /// `Project::root` of the projects listed in `projects`, one past the project directories
+    pub fn listed_root(&self) -> usize {
+        self.project_dirs.len()
+    }
+
+    /// Project directories in `Project::root` order, followed by the stand-in directory
+    /// for listed projects if there are any
+    pub fn roots(&self) -> impl Iterator<Item = &ProjectDir> {
+        let listed = (!self.projects.is_empty()).then_some(&*LISTED_PROJECTS);
+        self.project_dirs.iter().chain(listed)
+    }
+
+    /// Keys that quit as shown in the footer and help
+    pub fn quit_label(&self) -> String {
+        match &self.keybindings.quit {
+            Some(keys) => keys.to_string(),
+            None => self.quit_key.label().to_string(),
+        }
+    }
+
+    /// Whether Esc quits rather than clearing the search and filter
+    pub fn esc_quits(&self) -> bool {
+        match &self.keybindings.quit {
+            Some(keys) => keys.contains(KeyCode::Esc),
+            None => self.quit_key == QuitKey::Esc,
+        }
+    }
+
+    /// Listed project paths with `~` expanded, leaving out duplicates and projects that
+    /// scanning the project directories already finds
+    pub fn listed_projects(&self) -> Vec<PathBuf> {
+        let project_dirs: Vec<PathBuf> = self
+            .project_dirs
+            .iter()
+            .map(ProjectDir::expanded_path)
+            .collect();
+
+        let depth = self.scan_depth();
+        let mut listed: Vec<PathBuf> = Vec::new();
+        for path in &self.projects {
+            let path = PathBuf::from(shellexpand::tilde(path).into_owned());
+            let scanned = project_dirs.iter().any(|dir| scan_finds(dir, depth, &path));
+            if !scanned && !listed.contains(&path) {
+                listed.push(path);
+            }
+        }
+        listed
+    }
+
+    /// Opener for a project: the one in its `.porgi.toml`, then its root's, then the
+    /// global one
+    pub fn opener<'a>(&'a self, project: &'a Project) -> &'a ProjectOpener {
+        project
+            .config
+            .opener
+            .as_ref()
+            .or(self.root(project).opener.as_ref())
+            .unwrap_or(&self.opener)
+    }
+
+    /// How to retry openers that fail to start
+    pub fn open_retry(&self) -> SpawnRetry {
+        SpawnRetry {
+            retries: self.open_retries,
+            backoff: Duration::from_millis(
+                self.open_retry_backoff
+                    .unwrap_or(DEFAULT_OPEN_RETRY_BACKOFF),
+            ),
+        }
+    }
+
+    /// Readme file names to look for in order, falling back to the built-in list
+    pub fn readme_files(&self) -> Vec<&str> {
+        if self.readme_files.is_empty() {
+            DEFAULT_README_FILES.to_vec()
+        } else {
+            self.readme_files.iter().map(String::as_str).collect()
+        }
+    }
+
+    /// Project list width in percent, kept within bounds where both panes stay usable
+    pub fn split_ratio(&self) -> u16 {
+        clamp_split_ratio(self.split_ratio.unwrap_or(50))
+    }
+
+    /// How long to wait for the second key of sequences like `gg`
+    pub fn key_sequence_timeout(&self) -> Duration {
+        Duration::from_millis(
+            self.key_sequence_timeout
+                .unwrap_or(DEFAULT_KEY_SEQUENCE_TIMEOUT),
+        )
+    }
+
+    /// Levels down projects are looked for, at least 1 for the directory's subdirectories
+    pub fn scan_depth(&self) -> usize {
+        self.scan_depth.unwrap_or(1).max(1)
+    }
+
+    pub fn wrap_navigation(&self) -> bool {
+        self.wrap_navigation.unwrap_or(true)
+    }
+
+    pub fn watch(&self) -> bool {
+        self.watch.unwrap_or(false)
+    }
+
+    pub fn render_markdown(&self) -> bool {
+        self.render_markdown.unwrap_or(true)
+    }
+
+    /// Smallest terminal width and height porgi renders its layout in
+    pub fn min_terminal_size(&self) -> (u16, u16) {
+        let (width, height) = DEFAULT_MIN_TERMINAL_SIZE;
+        (
+            self.min_width.unwrap_or(width),
+            self.min_height.unwrap_or(height),
+        )
+    }
+
+    /// Write `split_ratio` to the config file it was loaded from, keeping the rest of
+    /// the file including comments as it is
+    pub fn save_split_ratio(&self, ratio: u16) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let contents = read_config_file(path)?;
+        let mut doc: toml_edit::DocumentMut = contents
+            .parse()
+            .wrap_err_with(|| format!("Invalid {}", path.display()))?;
+        doc["split_ratio"] = toml_edit::value(i64::from(ratio));
+
+        std::fs::write(path, doc.to_string())
+            .wrap_err_with(|| format!("Could not write {}", path.display()))
+    }
+
     fn get_paths() -> Vec<std::path::PathBuf> {
         let config_dir =
             dirs::config_dir().map(|config_dir| config_dir.join("porgi").join("porgi.toml"));
@@ -38,12 +551,23 @@ impl Config {
         }
     }
 
-    pub fn load() -> Result<Self> {
+    /// Load the config file at `path`, or search the default locations when it isn't
+    /// given, then apply `profile` or the `default` profile if there is one
+    pub fn load(path: Option<&Path>, profile: Option<&str>) -> Result<Self> {
+        let mut config = Self::load_file(path)?;
+        config.apply_profile(profile)?;
+        Ok(config)
+    }
+
+    fn load_file(path: Option<&Path>) -> Result<Self> {
+        if let Some(path) = path {
+            return Self::load_from(path);
+        }
+
         let paths = Self::get_paths();
 
-        if let Some(config_path) = paths.iter().find_map(must_exist) {
-            let config = std::fs::read_to_string(config_path)?;
-            Ok(toml::from_str(&config)?)
+        if let Some(config) = Self::load_first(&paths)? {
+            Ok(config)
         } else {
             eprintln!("No config file found. Please create one at in:");
             for path in paths {
@@ -53,4 +577,382 @@ impl Config {
             Ok(Self::default())
         }
     }
+
+    /// Load the first of `paths` that can be read. Paths that exist but can't be read are
+    /// skipped with a warning, their error is returned if no later path can be read.
+    fn load_first(paths: &[PathBuf]) -> Result<Option<Self>> {
+        let mut unreadable = None;
+
+        for path in paths.iter().filter(|path| path.exists()) {
+            match read_config_file(path) {
+                Ok(config) => return Self::parse(&config, path).map(Some),
+                Err(e) => {
+                    eprintln!("Skipping config: {:#}", e);
+                    unreadable = Some(e);
+                }
+            }
+        }
+
+        match unreadable {
+            Some(e) => Err(e),
+            None => Ok(None),
+        }
+    }
+
+    /// Replace the top-level settings with those of the profile `name`, or of the
+    /// `default` profile when no name is given and it exists
+    fn apply_profile(&mut self, name: Option<&str>) -> Result<()> {
+        let profile = match name {
+            Some(name) => self.profiles.get(name).ok_or_else(|| {
+                let mut names: Vec<_> = self.profiles.keys().map(String::as_str).collect();
+                names.sort_unstable();
+                let found = match self.path.as_deref() {
+                    Some(path) => format!(" in {}", path.display()),
+                    None => String::new(),
+                };
+                if names.is_empty() {
+                    eyre::eyre!("No profile named {:?}{}, none are defined", name, found)
+                } else {
+                    eyre::eyre!(
+                        "No profile named {:?}{}, the profiles are: {}",
+                        name,
+                        found,
+                        names.join(", ")
+                    )
+                }
+            })?,
+            None => match self.profiles.get("default") {
+                Some(profile) => profile,
+                None => return Ok(()),
+            },
+        };
+
+        let ProfileConfig {
+            project_dirs,
+            projects,
+            opener,
+            colors,
+        } = profile.clone();
+        if let Some(project_dirs) = project_dirs {
+            self.project_dirs = project_dirs;
+        }
+        if let Some(projects) = projects {
+            self.projects = projects;
+        }
+        if let Some(opener) = opener {
+            self.opener = opener;
+        }
+        if let Some(colors) = colors {
+            self.colors = colors;
+        }
+        Ok(())
+    }
+
+    /// Load the config file at `path`, which has to exist
+    fn load_from(path: &Path) -> Result<Self> {
+        Self::parse(&read_config_file(path)?, path)
+    }
+
+    fn parse(config: &str, path: &Path) -> Result<Self> {
+        let mut config: Self = parse_toml(config, path)?;
+        config.path = Some(path.to_path_buf());
+        Ok(config)
+    }
+
+    /// Use the common project directories that exist when `auto_discover_roots` is set and
+    /// nothing else is configured to be scanned
+    pub fn discover_roots(&mut self) {
+        self.discover_roots_in(COMMON_PROJECT_DIRS);
+    }
+
+    fn discover_roots_in(&mut self, candidates: &[&str]) {
+        if !self.auto_discover_roots || !self.project_dirs.is_empty() || !self.projects.is_empty() {
+            return;
+        }
+
+        self.project_dirs = candidates
+            .iter()
+            .map(|path| ProjectDir::new(*path))
+            .filter(|dir| dir.expanded_path().is_dir())
+            .collect();
+        self.roots_discovered = !self.project_dirs.is_empty();
+    }
+
+    /// Fill in every setting that falls back to a built-in default with the value used
+    pub fn with_defaults(mut self) -> Self {
+        self.readme_files = self.readme_files().into_iter().map(String::from).collect();
+        self.split_ratio = Some(self.split_ratio());
+        self.scan_depth = Some(self.scan_depth());
+        self.wrap_navigation = Some(self.wrap_navigation());
+        self.watch = Some(self.watch());
+        self.render_markdown = Some(self.render_markdown());
+        self.key_sequence_timeout = Some(self.key_sequence_timeout().as_millis() as u64);
+        self.open_retry_backoff = Some(self.open_retry().backoff.as_millis() as u64);
+
+        let (min_width, min_height) = self.min_terminal_size();
+        self.min_width = Some(min_width);
+        self.min_height = Some(min_height);
+
+        self
+    }
+
+    /// The config in `format`, noting which file it was loaded from
+    pub fn dump(&self, format: ConfigFormat) -> Result<String> {
+        match format {
+            ConfigFormat::Toml => {
+                let source = match &self.path {
+                    Some(path) => format!("# Loaded from {}", path.display()),
+                    None => "# No config file found, these are the defaults".to_string(),
+                };
+                Ok(format!("{}\n\n{}", source, toml::to_string(self)?))
+            }
+            ConfigFormat::Json => {
+                let mut value = serde_json::to_value(self)?;
+                value["config_path"] = serde_json::to_value(&self.path)?;
+                Ok(format!("{}\n", serde_json::to_string_pretty(&value)?))
+            }
+        }
+    }
+}
+
+/// Formats the resolved config can be printed in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ConfigFormat {
+    #[default]
+    Toml,
+    Json,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_dir;
+
+    #[test]
+    fn test_project_dirs_with_labels() {
+        let config: Config =
+            toml::from_str(r#"project_dirs = ["~/projects", { path = "~/work", label = "Work" }]"#)
+                .unwrap();
+
+        assert_eq!(config.project_dirs[0].path, "~/projects");
+        assert_eq!(config.project_dirs[0].display_name(false), "~/projects");
+        assert_eq!(config.project_dirs[1].path, "~/work");
+        assert_eq!(config.project_dirs[1].display_name(false), "Work");
+        assert_eq!(config.project_dirs[1].display_name(true), "Work");
+    }
+
+    #[test]
+    fn test_project_dir_opener() {
+        let config: Config = toml::from_str(
+            r#"
+            opener = "editor"
+            project_dirs = ["~/scratch", { path = "~/work", opener = "code" }]
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.project_dirs[0].opener.is_none());
+        assert!(matches!(
+            config.project_dirs[1].opener,
+            Some(ProjectOpener::Code)
+        ));
+    }
+
+    #[test]
+    fn test_dump_round_trips() {
+        let config: Config = toml::from_str(
+            r#"
+            project_dirs = ["~/scratch", { path = "~/work", label = "Work", opener = "code" }]
+            split_ratio = 90
+            "#,
+        )
+        .unwrap();
+        let config = config.with_defaults();
+        assert_eq!(config.split_ratio, Some(100 - MIN_SPLIT_RATIO));
+        assert_eq!(config.readme_files.len(), DEFAULT_README_FILES.len());
+
+        let dumped = config.dump(ConfigFormat::Toml).unwrap();
+        assert!(dumped.starts_with("# No config file found"));
+        let reloaded: Config = toml::from_str(&dumped).unwrap();
+        assert_eq!(reloaded.dump(ConfigFormat::Toml).unwrap(), dumped);
+        assert_eq!(reloaded.project_dirs[1].label.as_deref(), Some("Work"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&config.dump(ConfigFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["split_ratio"], 80);
+        assert!(json["config_path"].is_null());
+    }
+
+    #[test]
+    fn test_discover_roots() {
+        let dir = test_dir("discover-roots");
+        std::fs::create_dir(dir.join("src")).unwrap();
+        let src = dir.join("src").display().to_string();
+        let missing = dir.join("code").display().to_string();
+
+        let mut config = Config::default();
+        config.discover_roots_in(&[&missing, &src]);
+        assert!(config.project_dirs.is_empty());
+
+        config.auto_discover_roots = true;
+        config.discover_roots_in(&[&missing, &src]);
+        assert_eq!(config.project_dirs.len(), 1);
+        assert_eq!(config.project_dirs[0].path, src);
+        assert!(config.roots_discovered);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_skips_directory_config() {
+        let dir = test_dir("config-dir");
+        let as_dir = dir.join("a").join("porgi.toml");
+        let valid = dir.join("b").join("porgi.toml");
+        std::fs::create_dir_all(&as_dir).unwrap();
+        std::fs::create_dir_all(valid.parent().unwrap()).unwrap();
+        std::fs::write(&valid, r#"project_dirs = ["~/src"]"#).unwrap();
+
+        let config = Config::load_first(&[as_dir.clone(), valid])
+            .unwrap()
+            .unwrap();
+        assert_eq!(config.project_dirs[0].path, "~/src");
+
+        let err = Config::load_first(&[as_dir]).unwrap_err();
+        assert!(err.to_string().contains("is a directory"), "{err}");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_skips_unreadable_config() {
+        let dir = test_dir("config-unreadable");
+        let not_utf8 = dir.join("not_utf8.toml");
+        let valid = dir.join("valid.toml");
+        std::fs::write(&not_utf8, [0xff, 0xfe, 0xfd]).unwrap();
+        std::fs::write(&valid, r#"project_dirs = ["~/src"]"#).unwrap();
+
+        let config = Config::load_first(&[not_utf8.clone(), valid.clone()])
+            .unwrap()
+            .unwrap();
+        assert_eq!(config.project_dirs[0].path, "~/src");
+
+        let err = Config::load_first(std::slice::from_ref(&not_utf8)).unwrap_err();
+        assert!(err.to_string().contains("Could not read config file"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let no_permission = dir.join("no_permission.toml");
+            std::fs::write(&no_permission, r#"project_dirs = ["~/other"]"#).unwrap();
+            std::fs::set_permissions(&no_permission, std::fs::Permissions::from_mode(0o000))
+                .unwrap();
+
+            // Permissions don't stop root from reading the file
+            if std::fs::read(&no_permission).is_err() {
+                let config = Config::load_first(&[no_permission, valid])
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(config.project_dirs[0].path, "~/src");
+            }
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_given_path() {
+        let dir = test_dir("config-given");
+        let path = dir.join("work.toml");
+        std::fs::write(&path, r#"project_dirs = ["~/work"]"#).unwrap();
+
+        let config = Config::load(Some(&path), None).unwrap();
+        assert_eq!(config.project_dirs[0].path, "~/work");
+        assert_eq!(config.path.as_deref(), Some(path.as_path()));
+
+        // A given file isn't skipped over like the default locations are
+        let err = Config::load(Some(&dir.join("missing.toml")), None).unwrap_err();
+        assert!(err.to_string().contains("Could not read config file"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let path = Path::new("porgi.toml");
+        let err = parse_toml::<Config>("sort = \"name\"\ncolour = 1\n", path).unwrap_err();
+        assert!(
+            err.to_string().starts_with("unknown field `colour`"),
+            "{err}"
+        );
+        assert!(err.to_string().ends_with(" in porgi.toml:2:1"), "{err}");
+
+        let err = parse_toml::<Config>("[columns]\n  zzz = true\n", path).unwrap_err();
+        assert!(err.to_string().starts_with("unknown field `zzz`"), "{err}");
+        assert!(err.to_string().ends_with(" in porgi.toml:2:3"), "{err}");
+
+        let err = parse_toml::<Config>("split_ratio = \"x\"\n", path).unwrap_err();
+        assert!(err.to_string().ends_with(" in porgi.toml:1:15"), "{err}");
+    }
+
+    #[test]
+    fn test_profiles() {
+        let mut config: Config = toml::from_str(
+            r#"
+            project_dirs = ["~/src"]
+            opener = "editor"
+
+            [profiles.default]
+            project_dirs = ["~/personal"]
+
+            [profiles.work]
+            project_dirs = ["~/work"]
+            opener = "code"
+            "#,
+        )
+        .unwrap();
+
+        let mut default = Config {
+            profiles: config.profiles.clone(),
+            ..Default::default()
+        };
+        default.apply_profile(None).unwrap();
+        assert_eq!(default.project_dirs[0].path, "~/personal");
+
+        config.apply_profile(Some("work")).unwrap();
+        assert_eq!(config.project_dirs[0].path, "~/work");
+        assert!(matches!(config.opener, ProjectOpener::Code));
+
+        let err = config.apply_profile(Some("home")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"No profile named "home", the profiles are: default, work"#
+        );
+    }
+
+    #[test]
+    fn test_save_split_ratio() {
+        let dir = test_dir("config-split");
+        let path = dir.join("porgi.toml");
+        std::fs::write(&path, "# My projects\nproject_dirs = [\"~/src\"]\n").unwrap();
+
+        let config = Config::load_first(std::slice::from_ref(&path))
+            .unwrap()
+            .unwrap();
+        assert_eq!(config.split_ratio(), 50);
+        config.save_split_ratio(65).unwrap();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.starts_with("# My projects\n"));
+        let config = Config::load_first(&[path]).unwrap().unwrap();
+        assert_eq!(config.split_ratio(), 65);
+
+        let config = Config {
+            split_ratio: Some(95),
+            ..Default::default()
+        };
+        assert_eq!(config.split_ratio(), 80);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -4,35 +4,150 @@
 
 mod config;
 mod project;
+mod report;
+#[cfg(test)]
+mod test_util;
 mod tui;
 
+use chrono::TimeDelta;
+use clap::{Parser, Subcommand};
 use eyre::{anyhow, Result};
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use crate::{
-    config::Config,
-    project::ProjectLoader,
-    tui::{init_error_hooks, init_terminal, restore_terminal, App},
+    config::{Config, ConfigFormat, ProjectDir},
+    project::{Pins, ProjectLoader, ScanCache, SortKey, Tags},
+    report::ReportFormat,
+    tui::{init_error_hooks, init_terminal, report_working_directory, restore_terminal, App},
 };
 
+#[derive(Debug, Parser)]
+#[command(version, about, subcommand_precedence_over_arg = true)]
+struct Args {
+    /// Directories to scan for projects instead of those in the config file
+    #[arg(value_name = "DIR")]
+    dirs: Vec<PathBuf>,
+
+    /// Order to list projects in, overriding the config file
+    #[arg(long, value_enum)]
+    sort: Option<SortKey>,
+
+    /// Config file to use instead of searching the default locations
+    #[arg(short, long, value_name = "FILE", global = true)]
+    config: Option<PathBuf>,
+
+    /// Profile from the config file's `[profiles]` to use instead of `default`
+    #[arg(short, long, value_name = "NAME", global = true)]
+    profile: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Scan every project and print a report of dirty and stale projects, e.g. from cron
+    Report {
+        #[arg(long, value_enum, default_value_t)]
+        format: ReportFormat,
+        /// Projects not modified for this many days are reported as stale
+        #[arg(long, default_value_t = 90)]
+        stale_days: i64,
+        /// Number of the largest projects to list
+        #[arg(long, default_value_t = 10)]
+        largest: usize,
+    },
+    /// Print the config porgi is using, with defaults filled in
+    ConfigDump {
+        #[arg(long, value_enum, default_value_t)]
+        format: ConfigFormat,
+    },
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    let config = Arc::new(Config::load()?);
+    let args = Args::parse();
+
+    let mut config = Config::load(args.config.as_deref(), args.profile.as_deref())?;
 
-    if config.project_dirs.is_empty() {
-        return Err(anyhow!("No project directories configured"));
+    if let Some(sort) = args.sort {
+        config.sort = sort;
     }
 
-    let project_events = ProjectLoader::new(config.clone())?;
+    if !args.dirs.is_empty() {
+        config.project_dirs = args
+            .dirs
+            .iter()
+            .map(|dir| {
+                Ok(ProjectDir::new(
+                    std::path::absolute(dir)?.display().to_string(),
+                ))
+            })
+            .collect::<Result<_>>()?;
+        config.projects.clear();
+    }
+
+    config.discover_roots();
+
+    if let Some(Command::ConfigDump { format }) = args.command {
+        print!("{}", config.with_defaults().dump(format)?);
+        return Ok(());
+    }
+
+    let config = Arc::new(config);
+
+    if config.project_dirs.is_empty() && config.projects.is_empty() {
+        if config.auto_discover_roots {
+            return Err(anyhow!(
+                "No project directories configured and none of the common ones exist"
+            ));
+        }
+        return Err(anyhow!("No project directories or projects configured"));
+    }
+
+    if let Some(Command::Report {
+        format,
+        stale_days,
+        largest,
+    }) = args.command
+    {
+        let store = ProjectLoader::scan(config.clone()).await?;
+        print!(
+            "{}",
+            report::render(
+                &config,
+                &store,
+                TimeDelta::days(stale_days),
+                largest,
+                format
+            )
+        );
+        return Ok(());
+    }
+
+    let project_events = ProjectLoader::new(config.clone(), ScanCache::default_path())?;
 
     // setup terminal
     init_error_hooks()?;
     let terminal = init_terminal()?;
 
     // create app and run it
-    App::new(config, project_events).run(terminal).await?;
+    let mut app = App::new(config.clone(), project_events)
+        .with_pins(Pins::load(Pins::default_path()))
+        .with_tags(Tags::load(Tags::default_path()));
+    app.run(terminal).await?;
 
     restore_terminal()?;
 
+    if app.split_ratio() != config.split_ratio() {
+        if let Err(e) = config.save_split_ratio(app.split_ratio()) {
+            eprintln!("Could not save split_ratio: {:#}", e);
+        }
+    }
+
+    if let Some(path) = app.exit_dir() {
+        report_working_directory(path)?;
+    }
+
     Ok(())
 }
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use eyre::{Context, Result};
+
+use super::FileSummary;
+
+/// What walking each project found in the last completed scan, keyed by project path, so
+/// projects show their file count, size and modified time while being walked again
+#[derive(Debug, Default)]
+pub(crate) struct ScanCache {
+    summaries: HashMap<PathBuf, FileSummary>,
+}
+
+impl ScanCache {
+    /// Where the cache is kept between runs
+    pub(crate) fn default_path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("porgi").join("scan.json"))
+    }
+
+    /// Read the cache at `path`, starting empty if it is missing or unreadable. Projects
+    /// that no longer exist are left out.
+    pub(crate) fn load(path: &Path) -> Self {
+        let summaries: HashMap<PathBuf, FileSummary> = fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+
+        ScanCache {
+            summaries: summaries
+                .into_iter()
+                .filter(|(project, _)| project.is_dir())
+                .collect(),
+        }
+    }
+
+    pub(crate) fn get(&self, project: &Path) -> Option<&FileSummary> {
+        self.summaries.get(project)
+    }
+
+    pub(crate) fn insert(&mut self, project: PathBuf, summary: FileSummary) {
+        self.summaries.insert(project, summary);
+    }
+
+    pub(crate) fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Paths that aren't UTF-8 can't be JSON keys, those projects are only walked
+        let summaries: HashMap<&str, &FileSummary> = self
+            .summaries
+            .iter()
+            .filter_map(|(project, summary)| Some((project.to_str()?, summary)))
+            .collect();
+
+        fs::write(path, serde_json::to_vec(&summaries)?)
+            .wrap_err_with(|| format!("Could not write scan cache {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+    use crate::test_util::test_dir;
+
+    #[test]
+    fn test_scan_cache() {
+        let tmp = test_dir("cache");
+        fs::create_dir_all(tmp.join("kept")).unwrap();
+        let path = tmp.join("cache/scan.json");
+
+        let summary = FileSummary {
+            modified: SystemTime::UNIX_EPOCH + Duration::from_nanos(1_700_000_000_123_456_789),
+            file_count: 42,
+            size_bytes: 1024,
+            truncated: false,
+        };
+        let mut cache = ScanCache::default();
+        cache.insert(tmp.join("kept"), summary);
+        cache.insert(tmp.join("deleted"), summary);
+        cache.save(&path).unwrap();
+
+        let loaded = ScanCache::load(&path);
+        assert_eq!(loaded.get(&tmp.join("kept")), Some(&summary));
+        assert_eq!(loaded.get(&tmp.join("deleted")), None);
+
+        // A broken cache is started again rather than failing the scan
+        fs::write(&path, "{").unwrap();
+        assert!(ScanCache::load(&path).summaries.is_empty());
+
+        fs::remove_dir_all(&tmp).unwrap();
+    }
+}
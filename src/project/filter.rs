@@ -0,0 +1,108 @@
+use std::fmt;
+
+use chrono::TimeDelta;
+
+use super::{Project, ProjectKind, SearchQuery};
+
+/// Every predicate limiting which projects are listed, a project is shown only when it
+/// matches all of them
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Filter {
+    pub(crate) query: SearchQuery,
+    /// Only show git repositories
+    pub(crate) git_only: bool,
+    /// Only show git repositories with uncommitted changes
+    pub(crate) dirty_only: bool,
+    pub(crate) kind: Option<ProjectKind>,
+    pub(crate) modified: ModifiedFilter,
+}
+
+impl Filter {
+    /// Whether any predicate other than the search query is set
+    pub(crate) fn has_predicates(&self) -> bool {
+        self.git_only
+            || self.dirty_only
+            || self.kind.is_some()
+            || self.modified != ModifiedFilter::Any
+    }
+
+    pub(crate) fn matches(&self, project: &Project) -> bool {
+        self.query.matches(project)
+            && (!self.git_only || project.is_git())
+            && (!self.dirty_only || project.is_dirty())
+            && self
+                .kind
+                .as_ref()
+                .map_or(true, |kind| project.kind.as_ref() == Some(kind))
+            && self.modified.matches(project.age())
+    }
+
+    /// Short description of the predicates other than the search query
+    pub(crate) fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if self.git_only {
+            parts.push("git".to_string());
+        }
+        if self.dirty_only {
+            parts.push("dirty".to_string());
+        }
+        if let Some(kind) = &self.kind {
+            parts.push(kind.to_string());
+        }
+        if self.modified != ModifiedFilter::Any {
+            parts.push(format!("modified {}", self.modified));
+        }
+        parts.join(", ")
+    }
+}
+
+/// How recently a project must, or must not, have been modified
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum ModifiedFilter {
+    #[default]
+    Any,
+    LastWeek,
+    LastMonth,
+    OverAMonthAgo,
+    OverAYearAgo,
+}
+
+impl ModifiedFilter {
+    const ALL: [ModifiedFilter; 5] = [
+        ModifiedFilter::Any,
+        ModifiedFilter::LastWeek,
+        ModifiedFilter::LastMonth,
+        ModifiedFilter::OverAMonthAgo,
+        ModifiedFilter::OverAYearAgo,
+    ];
+
+    fn matches(self, age: TimeDelta) -> bool {
+        match self {
+            ModifiedFilter::Any => true,
+            ModifiedFilter::LastWeek => age <= TimeDelta::days(7),
+            ModifiedFilter::LastMonth => age <= TimeDelta::days(30),
+            ModifiedFilter::OverAMonthAgo => age > TimeDelta::days(30),
+            ModifiedFilter::OverAYearAgo => age > TimeDelta::days(365),
+        }
+    }
+
+    /// The next option, wrapping around. `forward` false goes backwards.
+    pub(crate) fn cycle(self, forward: bool) -> Self {
+        let idx = Self::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        let len = Self::ALL.len();
+        let next = if forward { idx + 1 } else { idx + len - 1 };
+        Self::ALL[next % len]
+    }
+}
+
+impl fmt::Display for ModifiedFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ModifiedFilter::Any => "any time",
+            ModifiedFilter::LastWeek => "in the last week",
+            ModifiedFilter::LastMonth => "in the last month",
+            ModifiedFilter::OverAMonthAgo => "over a month ago",
+            ModifiedFilter::OverAYearAgo => "over a year ago",
+        })
+    }
+}
@@ -0,0 +1,700 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use git2::{
+    BranchType, DiffOptions, DiffStatsFormat, Repository, Status, StatusOptions, SubmoduleIgnore,
+    SubmoduleStatus,
+};
+
+/// Issue tracker URL templates for well known forges, `{url}` is replaced by the repository URL
+const ISSUE_TRACKERS: &[(&str, &str)] = &[
+    ("github.com", "{url}/issues"),
+    ("gitlab.com", "{url}/-/issues"),
+    ("codeberg.org", "{url}/issues"),
+    ("gitea.com", "{url}/issues"),
+    ("bitbucket.org", "{url}/issues"),
+];
+
+/// Git information collected for a project that is a repository
+#[derive(Debug, Clone, Default)]
+pub(crate) struct GitStatus {
+    /// Checked out branch, or the short hash of the commit when HEAD is detached
+    pub(crate) branch: Option<String>,
+    /// HEAD points at a commit rather than a branch
+    pub(crate) detached: bool,
+    /// URL of the `origin` remote exactly as configured
+    pub(crate) remote_url: Option<String>,
+    /// Whether there are uncommitted changes, including untracked files
+    pub(crate) dirty: bool,
+    pub(crate) changes: Changes,
+    /// Commits the checked out branch is ahead of and behind its upstream, `None` when it
+    /// has no upstream
+    pub(crate) ahead_behind: Option<(usize, usize)>,
+    /// Branch work normally happens on, from `origin/HEAD` or a local `main` or `master`
+    pub(crate) default_branch: Option<String>,
+    /// Repository without a working tree, such as a mirror clone
+    pub(crate) bare: bool,
+    pub(crate) submodules: Submodules,
+}
+
+/// Counts of files with uncommitted changes, a file both staged and changed again since
+/// counts as both
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Changes {
+    pub(crate) staged: usize,
+    /// Changed in the working tree but not staged, including conflicts
+    pub(crate) modified: usize,
+    pub(crate) untracked: usize,
+}
+
+impl Changes {
+    fn from_repo(repo: &Repository) -> Self {
+        let mut changes = Changes::default();
+        let Ok(statuses) = repo.statuses(Some(
+            StatusOptions::new()
+                .include_untracked(true)
+                .include_ignored(false)
+                .exclude_submodules(true),
+        )) else {
+            return changes;
+        };
+
+        for entry in statuses.iter() {
+            let status = entry.status();
+            if status.intersects(
+                Status::INDEX_NEW
+                    | Status::INDEX_MODIFIED
+                    | Status::INDEX_DELETED
+                    | Status::INDEX_RENAMED
+                    | Status::INDEX_TYPECHANGE,
+            ) {
+                changes.staged += 1;
+            }
+            if status.intersects(
+                Status::WT_MODIFIED
+                    | Status::WT_DELETED
+                    | Status::WT_RENAMED
+                    | Status::WT_TYPECHANGE
+                    | Status::CONFLICTED,
+            ) {
+                changes.modified += 1;
+            }
+            if status.contains(Status::WT_NEW) {
+                changes.untracked += 1;
+            }
+        }
+
+        changes
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        *self == Changes::default()
+    }
+
+    /// Compact counts like `+3 ~5 ?2` for staged, modified and untracked files, leaving
+    /// out those with none
+    pub(crate) fn summary(&self) -> String {
+        [
+            ("+", self.staged),
+            ("~", self.modified),
+            ("?", self.untracked),
+        ]
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(symbol, count)| format!("{}{}", symbol, count))
+        .collect::<Vec<_>>()
+        .join(" ")
+    }
+}
+
+/// Counts of the repository's submodules needing attention
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Submodules {
+    pub(crate) total: usize,
+    /// Not checked out, `git submodule update --init` is needed
+    pub(crate) uninitialized: usize,
+    /// Checked out at a different commit than the repository records
+    pub(crate) out_of_date: usize,
+    /// Have uncommitted changes of their own
+    pub(crate) dirty: usize,
+}
+
+impl Submodules {
+    fn from_repo(repo: &Repository) -> Self {
+        let mut submodules = Submodules::default();
+
+        for submodule in repo.submodules().unwrap_or_default() {
+            let Some(name) = submodule.name() else {
+                continue;
+            };
+            let Ok(status) = repo.submodule_status(name, SubmoduleIgnore::None) else {
+                continue;
+            };
+
+            submodules.total += 1;
+            if status.contains(SubmoduleStatus::WD_UNINITIALIZED) {
+                submodules.uninitialized += 1;
+            } else if status.contains(SubmoduleStatus::WD_MODIFIED) {
+                submodules.out_of_date += 1;
+            }
+            if status.intersects(
+                SubmoduleStatus::WD_INDEX_MODIFIED
+                    | SubmoduleStatus::WD_WD_MODIFIED
+                    | SubmoduleStatus::WD_UNTRACKED,
+            ) {
+                submodules.dirty += 1;
+            }
+        }
+
+        submodules
+    }
+
+    /// Short description of the submodules needing attention, `None` if all are up to date
+    pub(crate) fn summary(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if self.uninitialized > 0 {
+            parts.push(format!("{} uninitialized", self.uninitialized));
+        }
+        if self.out_of_date > 0 {
+            parts.push(format!("{} out of date", self.out_of_date));
+        }
+        if self.dirty > 0 {
+            parts.push(format!("{} dirty", self.dirty));
+        }
+
+        (!parts.is_empty()).then(|| format!("submodules: {}", parts.join(", ")))
+    }
+}
+
+impl GitStatus {
+    /// Inspect `path`, returning `None` when it is not the root of a git repository.
+    ///
+    /// Parent directories are intentionally not searched so a project nested inside
+    /// another repository does not report the parent's status.
+    pub(crate) fn from_path(path: &Path) -> Option<Self> {
+        let repo = Repository::open(path).ok()?;
+
+        // The shorthand of a detached HEAD is just "HEAD"
+        let detached = repo.head_detached().unwrap_or(false);
+        let branch = repo.head().ok().and_then(|head| {
+            if detached {
+                let commit = head.peel_to_commit().ok()?;
+                let id = commit.as_object().short_id().ok()?;
+                id.as_str().map(str::to_string)
+            } else {
+                head.shorthand().map(str::to_string)
+            }
+        });
+
+        let remote_url = repo
+            .find_remote("origin")
+            .ok()
+            .and_then(|remote| remote.url().map(str::to_string));
+
+        let bare = repo.is_bare();
+
+        // Bare repositories have no working tree to have changes in
+        let changes = if bare {
+            Changes::default()
+        } else {
+            Changes::from_repo(&repo)
+        };
+        let dirty = !changes.is_empty();
+
+        let ahead_behind = ahead_behind(&repo);
+        let default_branch = default_branch(&repo);
+        let submodules = if bare {
+            Submodules::default()
+        } else {
+            Submodules::from_repo(&repo)
+        };
+
+        Some(GitStatus {
+            branch,
+            detached,
+            remote_url,
+            dirty,
+            changes,
+            ahead_behind,
+            default_branch,
+            bare,
+            submodules,
+        })
+    }
+
+    /// Whether a branch other than the default branch is checked out
+    pub(crate) fn off_default_branch(&self) -> bool {
+        match (&self.branch, &self.default_branch) {
+            (Some(branch), Some(default)) => branch != default,
+            _ => false,
+        }
+    }
+
+    /// Unpushed and unpulled commits like `↑2 ↓1`, `None` when there are neither or the
+    /// branch has no upstream
+    pub(crate) fn sync_summary(&self) -> Option<String> {
+        let (ahead, behind) = self.ahead_behind?;
+        let parts: Vec<String> = [("↑", ahead), ("↓", behind)]
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(arrow, count)| format!("{}{}", arrow, count))
+            .collect();
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+
+    /// Browsable https URL for the origin remote, if it can be derived
+    pub(crate) fn web_url(&self) -> Option<String> {
+        self.remote_url.as_deref().and_then(browsable_url)
+    }
+
+    /// Origin remote for display, as a browsable URL when one can be derived
+    pub(crate) fn display_remote(&self) -> Option<String> {
+        self.web_url().or_else(|| self.remote_url.clone())
+    }
+
+    /// URL that opens the repository in a web based editor (currently only GitHub's github.dev)
+    pub(crate) fn web_editor_url(&self) -> Option<String> {
+        let url = self.web_url()?;
+        let path = url.strip_prefix("https://github.com/")?;
+        Some(format!("https://github.dev/{}", path))
+    }
+
+    /// URL of the repository's issue tracker.
+    ///
+    /// `trackers` maps hosts to URL templates and takes precedence over the built-in forges,
+    /// allowing self-hosted forges to be added.
+    pub(crate) fn issues_url(&self, trackers: &HashMap<String, String>) -> Option<String> {
+        let url = self.web_url()?;
+        let host = url.strip_prefix("https://")?.split('/').next()?;
+
+        let template = trackers.get(host).map(String::as_str).or_else(|| {
+            ISSUE_TRACKERS
+                .iter()
+                .find(|(forge, _)| *forge == host)
+                .map(|(_, template)| *template)
+        })?;
+
+        Some(template.replace("{url}", &url))
+    }
+}
+
+fn ahead_behind(repo: &Repository) -> Option<(usize, usize)> {
+    let head = repo.head().ok()?;
+    if !head.is_branch() {
+        return None;
+    }
+
+    let upstream_name = repo.branch_upstream_name(head.name()?).ok()?;
+    let upstream = repo.find_reference(upstream_name.as_str()?).ok()?;
+    repo.graph_ahead_behind(head.target()?, upstream.target()?)
+        .ok()
+}
+
+fn default_branch(repo: &Repository) -> Option<String> {
+    // Set by clone to the branch the remote has checked out
+    let remote_head = repo
+        .find_reference("refs/remotes/origin/HEAD")
+        .ok()
+        .and_then(|head| head.symbolic_target().map(str::to_string))
+        .and_then(|target| {
+            target
+                .strip_prefix("refs/remotes/origin/")
+                .map(str::to_string)
+        });
+
+    remote_head.or_else(|| {
+        ["main", "master"]
+            .into_iter()
+            .find(|name| repo.find_branch(name, BranchType::Local).is_ok())
+            .map(str::to_string)
+    })
+}
+
+/// Commit time of the commit checked out in the repository at `path`, like
+/// `git log -1 --format=%ct`. `None` when it isn't a repository or has no commits.
+pub(crate) fn last_commit_time(path: &Path) -> Option<SystemTime> {
+    let repo = Repository::open(path).ok()?;
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    let seconds = u64::try_from(commit.time().seconds()).ok()?;
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+}
+
+/// Summary of uncommitted changes in the repository at `path` like `git diff --stat HEAD`,
+/// with untracked files counted as added
+pub(crate) fn diff_stat(path: &Path) -> Result<String, git2::Error> {
+    let repo = Repository::open(path)?;
+    // Repositories without commits yet have no HEAD, everything is then a change
+    let head = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+
+    let diff = repo.diff_tree_to_workdir_with_index(
+        head.as_ref(),
+        Some(
+            DiffOptions::new()
+                .include_untracked(true)
+                .recurse_untracked_dirs(true)
+                .show_untracked_content(true),
+        ),
+    )?;
+
+    let stats = diff.stats()?.to_buf(DiffStatsFormat::FULL, 80)?;
+    Ok(stats.as_str().unwrap_or_default().to_string())
+}
+
+/// Convert a remote URL into a `https://host/owner/repo` URL that can be opened in a browser.
+///
+/// Handles `https://`, `http://`, `ssh://` and `git://` URLs as well as the scp-like
+/// `git@host:owner/repo.git` form. Returns `None` for anything else such as local paths.
+pub(crate) fn browsable_url(remote: &str) -> Option<String> {
+    let remote = remote.trim();
+
+    let (host, path) = if let Some((scheme, rest)) = remote.split_once("://") {
+        if !matches!(scheme, "https" | "http" | "ssh" | "git" | "git+ssh") {
+            return None;
+        }
+        let (authority, path) = rest.split_once('/')?;
+        // Drop any user info and port, neither belong in a browsable URL
+        let host = authority.rsplit('@').next()?;
+        let host = host.split(':').next()?;
+        (host, path)
+    } else {
+        let (user_host, path) = remote.split_once(':')?;
+        let host = user_host.rsplit('@').next()?;
+        // Single letter hosts are windows drive letters rather than scp-like remotes
+        if host.len() < 2 || host.contains('/') {
+            return None;
+        }
+        (host, path)
+    };
+
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+
+    Some(format!("https://{}/{}", host, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_dir;
+
+    /// Time of every commit made by `commit`
+    const COMMIT_SECONDS: i64 = 1_700_000_000;
+
+    /// Fresh repository in a test directory named `name`
+    fn init_repo(name: &str) -> (std::path::PathBuf, Repository) {
+        let path = test_dir(name);
+        let repo = Repository::init(&path).unwrap();
+        (path, repo)
+    }
+
+    /// Commit the index with `parents`, updating `update_ref` if given. Commits are all
+    /// made at the same time so only their message, tree and parents tell them apart.
+    fn commit<'r>(
+        repo: &'r Repository,
+        update_ref: Option<&str>,
+        message: &str,
+        parents: &[&git2::Commit],
+    ) -> git2::Commit<'r> {
+        let time = git2::Time::new(COMMIT_SECONDS, 0);
+        let signature = git2::Signature::new("porgi", "porgi@example.com", &time).unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let id = repo
+            .commit(update_ref, &signature, &signature, message, &tree, parents)
+            .unwrap();
+        repo.find_commit(id).unwrap()
+    }
+
+    /// Commit the index on top of HEAD
+    fn commit_head(repo: &Repository) -> git2::Commit<'_> {
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        commit(repo, Some("HEAD"), "Commit", &parents)
+    }
+
+    #[test]
+    fn test_browsable_url() {
+        let cases = [
+            (
+                "git@github.com:foo/bar.git",
+                Some("https://github.com/foo/bar"),
+            ),
+            (
+                "https://github.com/foo/bar.git",
+                Some("https://github.com/foo/bar"),
+            ),
+            (
+                "https://github.com/foo/bar",
+                Some("https://github.com/foo/bar"),
+            ),
+            (
+                "ssh://git@gitlab.com:2222/foo/bar.git",
+                Some("https://gitlab.com/foo/bar"),
+            ),
+            (
+                "https://user@example.com/foo/bar/",
+                Some("https://example.com/foo/bar"),
+            ),
+            ("/srv/git/bar.git", None),
+            ("C:\\src\\bar", None),
+            ("file:///srv/git/bar.git", None),
+        ];
+
+        for (remote, expected) in cases {
+            assert_eq!(browsable_url(remote).as_deref(), expected, "{}", remote);
+        }
+    }
+
+    #[test]
+    fn test_display_remote() {
+        let status = |remote: &str| GitStatus {
+            remote_url: Some(remote.to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            status("git@github.com:foo/bar.git")
+                .display_remote()
+                .as_deref(),
+            Some("https://github.com/foo/bar")
+        );
+        // Local remotes have no web page so are shown as they are
+        assert_eq!(
+            status("/srv/git/bar.git").display_remote().as_deref(),
+            Some("/srv/git/bar.git")
+        );
+        assert_eq!(GitStatus::default().display_remote(), None);
+    }
+
+    #[test]
+    fn test_web_editor_url() {
+        let github = GitStatus {
+            remote_url: Some("git@github.com:foo/bar.git".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            github.web_editor_url().as_deref(),
+            Some("https://github.dev/foo/bar")
+        );
+
+        let gitlab = GitStatus {
+            remote_url: Some("git@gitlab.com:foo/bar.git".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(gitlab.web_editor_url(), None);
+    }
+
+    #[test]
+    fn test_dirty() {
+        let (path, repo) = init_repo("dirty");
+
+        assert!(!GitStatus::from_path(&path).unwrap().dirty);
+
+        std::fs::write(path.join("new.txt"), "untracked").unwrap();
+        assert!(GitStatus::from_path(&path).unwrap().dirty);
+
+        let stat = diff_stat(&path).unwrap();
+        assert!(stat.contains("new.txt"), "{stat}");
+        assert!(stat.contains("1 file changed, 1 insertion(+)"), "{stat}");
+
+        // Stage one file then change it again, leaving another untracked
+        std::fs::write(path.join("staged.txt"), "staged").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("staged.txt")).unwrap();
+        index.write().unwrap();
+        std::fs::write(path.join("staged.txt"), "changed again").unwrap();
+
+        let changes = GitStatus::from_path(&path).unwrap().changes;
+        assert_eq!(
+            changes,
+            Changes {
+                staged: 1,
+                modified: 1,
+                untracked: 1,
+            }
+        );
+        assert_eq!(changes.summary(), "+1 ~1 ?1");
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_bare() {
+        let dir = test_dir("bare");
+        let path = dir.join("repo.git");
+        Repository::init_bare(&path).unwrap();
+
+        let status = GitStatus::from_path(&path).unwrap();
+        assert!(status.bare);
+        assert!(!status.dirty);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_default_branch() {
+        let (path, repo) = init_repo("branch");
+
+        let commit = commit(&repo, None, "Initial", &[]);
+        repo.branch("main", &commit, false).unwrap();
+        repo.branch("feature", &commit, false).unwrap();
+
+        repo.set_head("refs/heads/main").unwrap();
+        let status = GitStatus::from_path(&path).unwrap();
+        assert_eq!(status.default_branch.as_deref(), Some("main"));
+        assert!(!status.off_default_branch());
+
+        repo.set_head("refs/heads/feature").unwrap();
+        assert!(GitStatus::from_path(&path).unwrap().off_default_branch());
+
+        // origin/HEAD wins over local branch names
+        repo.reference("refs/remotes/origin/develop", commit.id(), false, "")
+            .unwrap();
+        repo.reference_symbolic(
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/develop",
+            false,
+            "",
+        )
+        .unwrap();
+        let status = GitStatus::from_path(&path).unwrap();
+        assert_eq!(status.default_branch.as_deref(), Some("develop"));
+
+        repo.set_head_detached(commit.id()).unwrap();
+        let status = GitStatus::from_path(&path).unwrap();
+        assert!(status.detached);
+        let short_id = commit.as_object().short_id().unwrap();
+        assert_eq!(status.branch.as_deref(), short_id.as_str());
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_last_commit_time() {
+        let (path, repo) = init_repo("time");
+        assert_eq!(last_commit_time(&path), None);
+
+        commit_head(&repo);
+        assert_eq!(
+            last_commit_time(&path),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(COMMIT_SECONDS as u64))
+        );
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_ahead_behind() {
+        let (path, repo) = init_repo("ahead");
+        // Messages differ so the commits get different ids
+        let base = commit(&repo, None, "Base", &[]);
+        repo.branch("main", &base, false).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        assert_eq!(GitStatus::from_path(&path).unwrap().ahead_behind, None);
+
+        repo.remote("origin", "https://example.com/porgi.git")
+            .unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("branch.main.remote", "origin").unwrap();
+        config
+            .set_str("branch.main.merge", "refs/heads/main")
+            .unwrap();
+
+        // Two commits only on main, one only on origin/main
+        let local = commit(
+            &repo,
+            None,
+            "Local 2",
+            &[&commit(&repo, None, "Local 1", &[&base])],
+        );
+        repo.reference("refs/heads/main", local.id(), true, "")
+            .unwrap();
+        let remote = commit(&repo, None, "Remote", &[&base]);
+        repo.reference("refs/remotes/origin/main", remote.id(), false, "")
+            .unwrap();
+
+        let status = GitStatus::from_path(&path).unwrap();
+        assert_eq!(status.ahead_behind, Some((2, 1)));
+        assert_eq!(status.sync_summary().as_deref(), Some("↑2 ↓1"));
+
+        repo.reference("refs/remotes/origin/main", local.id(), true, "")
+            .unwrap();
+        let status = GitStatus::from_path(&path).unwrap();
+        assert_eq!(status.ahead_behind, Some((0, 0)));
+        assert_eq!(status.sync_summary(), None);
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_submodules() {
+        let dir = test_dir("submodules");
+        let upstream = Repository::init(dir.join("upstream")).unwrap();
+        commit_head(&upstream);
+
+        let path = dir.join("project");
+        let repo = Repository::init(&path).unwrap();
+        let url = format!("file://{}", dir.join("upstream").display());
+        let mut submodule = repo.submodule(&url, Path::new("sub"), true).unwrap();
+        submodule.clone(None).unwrap();
+        submodule.add_finalize().unwrap();
+        commit_head(&repo);
+
+        let status = GitStatus::from_path(&path).unwrap();
+        assert_eq!(status.submodules.total, 1);
+        assert_eq!(status.submodules.summary(), None);
+
+        let sub = Repository::open(path.join("sub")).unwrap();
+        commit_head(&sub);
+        std::fs::write(path.join("sub").join("new.txt"), "untracked").unwrap();
+        assert_eq!(
+            GitStatus::from_path(&path)
+                .unwrap()
+                .submodules
+                .summary()
+                .as_deref(),
+            Some("submodules: 1 out of date, 1 dirty")
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_issues_url() {
+        let status = |remote: &str| GitStatus {
+            remote_url: Some(remote.to_string()),
+            ..Default::default()
+        };
+        let trackers =
+            HashMap::from([("git.example.com".to_string(), "{url}/tickets".to_string())]);
+
+        assert_eq!(
+            status("git@github.com:foo/bar.git")
+                .issues_url(&trackers)
+                .as_deref(),
+            Some("https://github.com/foo/bar/issues")
+        );
+        assert_eq!(
+            status("git@gitlab.com:foo/bar.git")
+                .issues_url(&trackers)
+                .as_deref(),
+            Some("https://gitlab.com/foo/bar/-/issues")
+        );
+        assert_eq!(
+            status("https://git.example.com/foo/bar")
+                .issues_url(&trackers)
+                .as_deref(),
+            Some("https://git.example.com/foo/bar/tickets")
+        );
+        assert_eq!(
+            status("https://unknown.example.com/foo/bar").issues_url(&trackers),
+            None
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use super::Project;
+
+/// Files and directories whose presence means a project has CI set up
+const CI_MARKERS: &[&str] = &[
+    ".github/workflows",
+    ".gitlab-ci.yml",
+    ".circleci",
+    ".travis.yml",
+    ".woodpecker.yml",
+    ".woodpecker",
+    ".forgejo/workflows",
+    "azure-pipelines.yml",
+    "Jenkinsfile",
+];
+
+pub(crate) fn has_ci(path: &Path) -> bool {
+    CI_MARKERS.iter().any(|marker| path.join(marker).exists())
+}
+
+/// How much each signal counts towards a project's health score. The score is the
+/// share of the total weight whose signals a project has, scaled to 0-100.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthConfig {
+    /// Has a readme
+    pub readme: u32,
+    /// Is a git repository
+    pub git: u32,
+    /// Is a git repository without uncommitted changes
+    pub clean: u32,
+    /// Was modified within `recent_days`
+    pub recent: u32,
+    /// Has CI configured, e.g. `.github/workflows` or `.gitlab-ci.yml`
+    pub ci: u32,
+    pub recent_days: u64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            readme: 20,
+            git: 20,
+            clean: 20,
+            recent: 20,
+            ci: 20,
+            recent_days: 30,
+        }
+    }
+}
+
+impl HealthConfig {
+    /// Weight of each signal with whether `project` has it at `now` and how to describe it
+    /// missing
+    fn signals(
+        &self,
+        project: &Project,
+        now: SystemTime,
+    ) -> [(u32, bool, Option<&'static str>); 5] {
+        let recent_since = now
+            .checked_sub(Duration::from_secs(
+                self.recent_days.saturating_mul(24 * 60 * 60),
+            ))
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+
+        [
+            (
+                self.readme,
+                project.readme_path.is_some(),
+                Some("no README"),
+            ),
+            (self.git, project.is_git(), Some("no git")),
+            // Without git there are no changes to mention, missing git already is
+            (
+                self.clean,
+                project.is_git() && !project.is_dirty(),
+                project.is_git().then_some("uncommitted changes"),
+            ),
+            (
+                self.recent,
+                project.modified >= recent_since,
+                Some("not modified recently"),
+            ),
+            (self.ci, project.has_ci, Some("no CI")),
+        ]
+    }
+
+    /// Health of `project` at `now` from 0 to 100, projects score 100 when every weight is 0
+    pub(crate) fn score(&self, project: &Project, now: SystemTime) -> u8 {
+        let signals = self.signals(project, now);
+        let total: u32 = signals.iter().map(|(weight, _, _)| weight).sum();
+        if total == 0 {
+            return 100;
+        }
+
+        let met: u32 = signals
+            .iter()
+            .filter(|(_, met, _)| *met)
+            .map(|(weight, _, _)| weight)
+            .sum();
+        (u64::from(met) * 100 / u64::from(total)) as u8
+    }
+
+    /// Signals counting towards the score that `project` is missing
+    pub(crate) fn missing(&self, project: &Project, now: SystemTime) -> Vec<&'static str> {
+        self.signals(project, now)
+            .into_iter()
+            .filter(|(weight, met, _)| *weight > 0 && !met)
+            .filter_map(|(_, _, reason)| reason)
+            .collect()
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+/// What sort of project a directory is, detected from marker files in its root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ProjectKind {
+    Rust,
+    JavaScript,
+    Go,
+    Python,
+    Ruby,
+    Java,
+    Elixir,
+    Php,
+    Swift,
+    Dart,
+    Zig,
+    Cpp,
+    /// Kind named by a user configured marker
+    Custom(String),
+}
+
+/// Built-in marker files, checked in order so the more specific markers come first
+const MARKERS: &[(&str, ProjectKind)] = &[
+    ("Cargo.toml", ProjectKind::Rust),
+    ("go.mod", ProjectKind::Go),
+    ("pyproject.toml", ProjectKind::Python),
+    ("setup.py", ProjectKind::Python),
+    ("Gemfile", ProjectKind::Ruby),
+    ("pom.xml", ProjectKind::Java),
+    ("build.gradle", ProjectKind::Java),
+    ("build.gradle.kts", ProjectKind::Java),
+    ("mix.exs", ProjectKind::Elixir),
+    ("composer.json", ProjectKind::Php),
+    ("Package.swift", ProjectKind::Swift),
+    ("pubspec.yaml", ProjectKind::Dart),
+    ("build.zig", ProjectKind::Zig),
+    ("CMakeLists.txt", ProjectKind::Cpp),
+    ("package.json", ProjectKind::JavaScript),
+    ("requirements.txt", ProjectKind::Python),
+];
+
+impl ProjectKind {
+    /// Detect the kind of project at `path`.
+    ///
+    /// `custom_markers` maps marker file names to kind names and is checked before the
+    /// built-in markers so users can override them. When several custom markers match
+    /// the alphabetically first file name wins.
+    pub(crate) fn detect(path: &Path, custom_markers: &HashMap<String, String>) -> Option<Self> {
+        let mut custom: Vec<_> = custom_markers.iter().collect();
+        custom.sort();
+
+        custom
+            .into_iter()
+            .find(|(marker, _)| path.join(marker).exists())
+            .map(|(_, kind)| ProjectKind::Custom(kind.clone()))
+            .or_else(|| {
+                MARKERS
+                    .iter()
+                    .find(|(marker, _)| path.join(marker).exists())
+                    .map(|(_, kind)| kind.clone())
+            })
+    }
+}
+
+impl fmt::Display for ProjectKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ProjectKind::Rust => "Rust",
+            ProjectKind::JavaScript => "JavaScript",
+            ProjectKind::Go => "Go",
+            ProjectKind::Python => "Python",
+            ProjectKind::Ruby => "Ruby",
+            ProjectKind::Java => "Java",
+            ProjectKind::Elixir => "Elixir",
+            ProjectKind::Php => "PHP",
+            ProjectKind::Swift => "Swift",
+            ProjectKind::Dart => "Dart",
+            ProjectKind::Zig => "Zig",
+            ProjectKind::Cpp => "C/C++",
+            ProjectKind::Custom(name) => name,
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_kind() {
+        let workspace = Path::new("tests/test_workspaces/cargo_workspace");
+
+        assert_eq!(
+            ProjectKind::detect(workspace, &HashMap::new()),
+            Some(ProjectKind::Rust)
+        );
+
+        let custom = HashMap::from([("Cargo.toml".to_string(), "Workspace".to_string())]);
+        assert_eq!(
+            ProjectKind::detect(workspace, &custom),
+            Some(ProjectKind::Custom("Workspace".to_string()))
+        );
+
+        assert_eq!(
+            ProjectKind::detect(
+                Path::new("tests/test_projects/test_project_a"),
+                &HashMap::new()
+            ),
+            None
+        );
+    }
+}
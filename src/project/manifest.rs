@@ -0,0 +1,61 @@
+use std::path::Path;
+
+/// One line description from the project's `Cargo.toml` or `package.json`, `None` when
+/// neither has one or they can't be parsed
+pub(crate) fn description(path: &Path) -> Option<String> {
+    cargo_description(path)
+        .or_else(|| package_json_description(path))
+        .map(|description| description.trim().to_string())
+        .filter(|description| !description.is_empty())
+}
+
+fn cargo_description(path: &Path) -> Option<String> {
+    let manifest: toml::Table = std::fs::read_to_string(path.join("Cargo.toml"))
+        .ok()?
+        .parse()
+        .ok()?;
+
+    // Workspaces can set a description shared by their members
+    let package = manifest.get("package").or_else(|| {
+        manifest
+            .get("workspace")
+            .and_then(|workspace| workspace.get("package"))
+    })?;
+    package.get("description")?.as_str().map(str::to_string)
+}
+
+fn package_json_description(path: &Path) -> Option<String> {
+    let manifest: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(path.join("package.json")).ok()?).ok()?;
+    manifest.get("description")?.as_str().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_dir;
+
+    #[test]
+    fn test_description() {
+        let path = test_dir("manifest");
+        assert_eq!(description(&path), None);
+
+        std::fs::write(path.join("package.json"), r#"{"description": "From npm"}"#).unwrap();
+        assert_eq!(description(&path).as_deref(), Some("From npm"));
+
+        // Cargo.toml wins, a broken one falls back to package.json
+        std::fs::write(
+            path.join("Cargo.toml"),
+            "[package]\ndescription = \" A corgi \"\n",
+        )
+        .unwrap();
+        assert_eq!(description(&path).as_deref(), Some("A corgi"));
+        std::fs::write(path.join("Cargo.toml"), "[package\n").unwrap();
+        assert_eq!(description(&path).as_deref(), Some("From npm"));
+
+        std::fs::write(path.join("package.json"), "{").unwrap();
+        assert_eq!(description(&path), None);
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+}
@@ -0,0 +1,2729 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ffi::OsString;
+use std::fmt::Write;
+use std::io;
+use std::ops::Index;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Local, TimeDelta};
+use eyre::{anyhow, bail, Context};
+use eyre::{OptionExt, Result};
+use fancy_duration::{DurationPart, FancyDuration};
+use futures::{stream, FutureExt, Stream, StreamExt, TryStreamExt};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::WalkBuilder;
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use serde::{Deserialize, Serialize};
+use tokio::process;
+use tokio::sync::mpsc::{Receiver, Sender};
+use tokio_stream::wrappers::ReceiverStream;
+use which::which;
+
+use crate::config::{Config, ProjectConfig, ProjectDir, WalkConfig};
+use git::last_commit_time;
+
+mod cache;
+mod filter;
+mod git;
+mod health;
+mod kind;
+mod manifest;
+mod pins;
+mod search;
+mod tags;
+mod template;
+mod workspace;
+
+pub(crate) use cache::ScanCache;
+pub(crate) use filter::Filter;
+pub(crate) use git::{diff_stat, GitStatus};
+pub(crate) use health::HealthConfig;
+pub(crate) use kind::ProjectKind;
+pub(crate) use pins::Pins;
+pub(crate) use search::SearchQuery;
+pub(crate) use tags::{parse_tags, Tags};
+pub(crate) use template::{copy_template, template_destination};
+
+pub(crate) type ProjectKey = PathBuf;
+
+pub(crate) enum ProjectEvent {
+    Add(Box<Project>),
+    /// The project has been walked
+    Update(ProjectKey, FileSummary),
+    /// Every project the scan found has been walked, projects the watcher finds after
+    /// this are still sent
+    Scanned,
+    /// Watching for changes hit an error, projects already listed are kept
+    WatchFailed(String),
+}
+
+/// What walking a project's files found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct FileSummary {
+    /// Newest modified time of the project directory and its files
+    pub(crate) modified: std::time::SystemTime,
+    pub(crate) file_count: usize,
+    /// Total size in bytes
+    pub(crate) size_bytes: u64,
+    /// The walk stopped at `max_walk_files`, there are more files than counted
+    pub(crate) truncated: bool,
+}
+
+/// Order projects are displayed in
+///
+/// Every key falls back to comparing names then paths so the order never depends on
+/// the order projects were discovered in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    /// Most recently modified first
+    #[default]
+    Modified,
+    /// Alphabetically by name
+    Name,
+    /// Healthiest first, see `HealthConfig`
+    Health,
+    /// Most files first
+    FileCount,
+}
+
+impl SortKey {
+    /// Arrow showing which way the sorted column runs, up for ascending
+    pub(crate) fn indicator(&self) -> &'static str {
+        match self {
+            SortKey::Modified => "↓",
+            SortKey::Name => "↑",
+            SortKey::Health => "↓",
+            SortKey::FileCount => "↓",
+        }
+    }
+
+    /// Key (s) switches to from this one
+    pub(crate) fn next(self) -> Self {
+        match self {
+            SortKey::Modified => SortKey::Name,
+            SortKey::Name => SortKey::FileCount,
+            SortKey::FileCount => SortKey::Health,
+            SortKey::Health => SortKey::Modified,
+        }
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            SortKey::Modified => "modified",
+            SortKey::Name => "name",
+            SortKey::Health => "health",
+            SortKey::FileCount => "files",
+        }
+    }
+
+    pub(crate) fn compare(&self, a: &Project, b: &Project) -> Ordering {
+        let by_key = match self {
+            SortKey::Modified => b.modified.cmp(&a.modified),
+            SortKey::Name => Ordering::Equal,
+            SortKey::Health => b.health.cmp(&a.health),
+            SortKey::FileCount => b.file_count.cmp(&a.file_count),
+        };
+
+        by_key
+            .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.path.cmp(&b.path))
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ProjectStore {
+    project_by_key: HashMap<ProjectKey, usize>,
+    /// Workspace members of each project, so they move with their workspace without
+    /// searching every project
+    members: HashMap<ProjectKey, Vec<usize>>,
+    display_order: Vec<usize>,
+    projects: Vec<Project>,
+    sort_key: SortKey,
+    /// Weights used to score projects when sorting by health
+    health: HealthConfig,
+    filter: Filter,
+    /// Projects listed before the rest, sorted among themselves by `sort_key`
+    pins: Pins,
+    /// Tags of every project, copied onto projects as they are added
+    tags: Tags,
+}
+
+impl ProjectStore {
+    pub(crate) fn new(sort_key: SortKey) -> Self {
+        ProjectStore {
+            sort_key,
+            ..Default::default()
+        }
+    }
+
+    /// Score project health with `health` rather than the default weights
+    pub(crate) fn with_health(self, health: HealthConfig) -> Self {
+        ProjectStore { health, ..self }
+    }
+
+    /// List the projects in `pins` first
+    pub(crate) fn with_pins(self, pins: Pins) -> Self {
+        ProjectStore { pins, ..self }
+    }
+
+    /// Give projects the tags in `tags`
+    pub(crate) fn with_tags(self, tags: Tags) -> Self {
+        ProjectStore { tags, ..self }
+    }
+
+    /// Replace the project's tags, saving them for the next run. The project keeps its
+    /// tags if they can't be saved, as `Tags` does.
+    pub(crate) fn set_tags(&mut self, key: &ProjectKey, tags: Vec<String>) -> Result<()> {
+        self.tags.set(key, tags.clone())?;
+        if let Some(idx) = self.project_by_key.get(key).copied() {
+            self.projects[idx].tags = tags;
+            // Tags can move the project in or out of a tag search
+            self.reposition(idx);
+        }
+        Ok(())
+    }
+
+    /// Whether the project is pinned, workspace members never are as they are listed
+    /// with their workspace
+    pub(crate) fn is_pinned(&self, project: &Project) -> bool {
+        project.parent.is_none() && self.pins.contains(project.key())
+    }
+
+    /// Pin the project or unpin it if it already is, returning whether it is now pinned
+    pub(crate) fn toggle_pin(&mut self, key: &ProjectKey) -> Result<bool> {
+        // Members pinned before their workspace was expanded can still be unpinned
+        let member = self
+            .get_by_key(key)
+            .is_some_and(|project| project.parent.is_some());
+        if member && !self.pins.contains(key) {
+            bail!("Workspace members are listed with their workspace, pin it instead");
+        }
+
+        let pinned = self.pins.toggle(key)?;
+        self.sort();
+        Ok(pinned)
+    }
+
+    /// Display order of the projects at `a` and `b`, workspace members sort as their
+    /// workspace and come directly after it
+    fn order(&self, a: usize, b: usize) -> Ordering {
+        let workspace = |idx: usize| {
+            let project = &self.projects[idx];
+            project
+                .parent
+                .as_ref()
+                .and_then(|key| self.project_by_key.get(key))
+                .map_or(project, |parent_idx| &self.projects[*parent_idx])
+        };
+
+        let (project_a, project_b) = (&self.projects[a], &self.projects[b]);
+        let (workspace_a, workspace_b) = (workspace(a), workspace(b));
+        self.is_pinned(workspace_b)
+            .cmp(&self.is_pinned(workspace_a))
+            .then_with(|| self.sort_key.compare(workspace_a, workspace_b))
+            .then_with(|| project_a.parent.is_some().cmp(&project_b.parent.is_some()))
+            .then_with(|| project_a.path.cmp(&project_b.path))
+    }
+
+    /// Sort projects by the sort key, workspace members are kept together directly after
+    /// their workspace
+    pub(crate) fn sort(&mut self) {
+        // Scored once with the same time so recency can't change mid-sort
+        let now = SystemTime::now();
+        for project in &mut self.projects {
+            project.health = self.health.score(project, now);
+        }
+
+        let mut display_order = std::mem::take(&mut self.display_order);
+        display_order.sort_by(|a, b| self.order(*a, *b));
+        self.display_order = display_order;
+    }
+
+    /// Sort projects by `sort_key` from now on
+    pub(crate) fn sort_by(&mut self, sort_key: SortKey) {
+        self.sort_key = sort_key;
+        self.sort();
+    }
+
+    /// Move the project at `idx` and its workspace members to where sorting would put
+    /// them, adding or removing them from display as the filter says. This saves sorting
+    /// every project each time one is added or walked during a scan.
+    fn reposition(&mut self, idx: usize) {
+        let members = self.members.get(self.projects[idx].key());
+        let moved: Vec<usize> = std::iter::once(idx)
+            .chain(members.into_iter().flatten().copied())
+            .collect();
+
+        // Everything moving is taken out first so the rest stay sorted to search through
+        self.display_order.retain(|shown| !moved.contains(shown));
+        for idx in moved {
+            if self.filter.matches(&self.projects[idx]) {
+                let position = self
+                    .display_order
+                    .partition_point(|shown| self.order(*shown, idx) == Ordering::Less);
+                self.display_order.insert(position, idx);
+            }
+        }
+    }
+
+    pub(crate) fn add(&mut self, mut project: Project) {
+        let key = project.key().clone();
+        project.tags = self.tags.get(&key).to_vec();
+        project.health = self.health.score(&project, SystemTime::now());
+
+        // Rescans add projects again, keep the walked summary until it is walked again
+        if let Some(idx) = self.project_by_key.get(&key).copied() {
+            let existing = &self.projects[idx];
+            project.modified = existing.modified;
+            project.file_count = existing.file_count;
+            project.size_bytes = existing.size_bytes;
+            project.file_count_truncated = existing.file_count_truncated;
+            if project.parent != existing.parent {
+                if let Some(members) = existing
+                    .parent
+                    .as_ref()
+                    .and_then(|parent| self.members.get_mut(parent))
+                {
+                    members.retain(|member| *member != idx);
+                }
+                if let Some(parent) = &project.parent {
+                    self.members.entry(parent.clone()).or_default().push(idx);
+                }
+            }
+            self.projects[idx] = project;
+            self.reposition(idx);
+            return;
+        }
+
+        let idx = self.projects.len();
+        if let Some(parent) = &project.parent {
+            self.members.entry(parent.clone()).or_default().push(idx);
+        }
+        self.projects.push(project);
+        if self.project_by_key.insert(key, idx).is_some() {
+            panic!("Duplicate project key");
+        }
+        self.reposition(idx);
+    }
+
+    /// Number of projects shown, i.e. those matching the current filter
+    pub(crate) fn len(&self) -> usize {
+        self.display_order.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.display_order.is_empty()
+    }
+
+    pub(crate) fn get(&self, index: usize) -> Option<&Project> {
+        self.display_order
+            .get(index)
+            .map(|idx| &self.projects[*idx])
+    }
+
+    pub(crate) fn query(&self) -> &SearchQuery {
+        &self.filter.query
+    }
+
+    /// Position of the shown project whose name best matches the search query, the first
+    /// shown when several match equally well or none match by name
+    pub(crate) fn best_match(&self) -> Option<usize> {
+        let query = &self.filter.query;
+        self.iter()
+            .enumerate()
+            .max_by_key(|(position, project)| {
+                // Earlier positions win ties as max_by_key otherwise returns the last
+                (
+                    query.name_score(project).unwrap_or(i64::MIN),
+                    std::cmp::Reverse(*position),
+                )
+            })
+            .map(|(position, _)| position)
+    }
+
+    /// Only show projects matching `query`, keeping the rest of the filter
+    pub(crate) fn set_query(&mut self, query: SearchQuery) {
+        self.filter.query = query;
+        self.refilter();
+    }
+
+    pub(crate) fn filter(&self) -> &Filter {
+        &self.filter
+    }
+
+    /// Only show projects matching `filter`
+    pub(crate) fn set_filter(&mut self, filter: Filter) {
+        self.filter = filter;
+        self.refilter();
+    }
+
+    /// Combined size of all projects, shown or not
+    pub(crate) fn total_size(&self) -> u64 {
+        self.projects.iter().map(|project| project.size_bytes).sum()
+    }
+
+    pub(crate) fn sort_key(&self) -> SortKey {
+        self.sort_key
+    }
+
+    /// Distinct kinds of all projects, shown or not, ordered by name
+    pub(crate) fn kinds(&self) -> Vec<ProjectKind> {
+        let mut kinds: Vec<ProjectKind> = Vec::new();
+        for kind in self.projects.iter().filter_map(|p| p.kind.as_ref()) {
+            if !kinds.contains(kind) {
+                kinds.push(kind.clone());
+            }
+        }
+        kinds.sort_by_key(ToString::to_string);
+        kinds
+    }
+
+    /// Drop projects whose directories have been removed
+    pub(crate) fn remove_missing(&mut self) {
+        self.retain(|project| project.path.exists());
+    }
+
+    /// Drop a project from the store, it comes back if a rescan finds it again
+    pub(crate) fn remove(&mut self, key: &ProjectKey) {
+        self.retain(|project| project.key() != key);
+    }
+
+    fn retain(&mut self, f: impl FnMut(&Project) -> bool) {
+        self.projects.retain(f);
+        self.project_by_key = self
+            .projects
+            .iter()
+            .enumerate()
+            .map(|(idx, project)| (project.key().clone(), idx))
+            .collect();
+        self.members.clear();
+        for (idx, project) in self.projects.iter().enumerate() {
+            if let Some(parent) = &project.parent {
+                self.members.entry(parent.clone()).or_default().push(idx);
+            }
+        }
+        self.refilter();
+    }
+
+    fn refilter(&mut self) {
+        let projects = &self.projects;
+        let filter = &self.filter;
+        self.display_order = (0..projects.len())
+            .filter(|idx| filter.matches(&projects[*idx]))
+            .collect();
+        self.sort();
+    }
+
+    pub(crate) fn get_mut(&mut self, key: &ProjectKey) -> Option<&mut Project> {
+        self.project_by_key
+            .get(key)
+            .map(|idx| &mut self.projects[*idx])
+    }
+
+    /// Project with `key`, whether or not the filter shows it
+    pub(crate) fn get_by_key(&self, key: &ProjectKey) -> Option<&Project> {
+        self.project_by_key.get(key).map(|idx| &self.projects[*idx])
+    }
+
+    /// Position of the project with `key` in display order
+    pub(crate) fn position(&self, key: &ProjectKey) -> Option<usize> {
+        let idx = self.project_by_key.get(key)?;
+        self.display_order.iter().position(|i| i == idx)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Project> {
+        self.display_order
+            .iter()
+            .map(move |idx| &self.projects[*idx])
+    }
+
+    pub(crate) fn update(&mut self, event: ProjectEvent) -> Result<()> {
+        match event {
+            ProjectEvent::Add(project) => {
+                self.add(*project);
+            }
+            ProjectEvent::Update(project_key, summary) => {
+                // The project may have been removed since it was walked
+                let Some(idx) = self.project_by_key.get(&project_key).copied() else {
+                    return Ok(());
+                };
+
+                let project = &mut self.projects[idx];
+                project.modified = summary.modified;
+                project.file_count = summary.file_count;
+                project.size_bytes = summary.size_bytes;
+                project.file_count_truncated = summary.truncated;
+                project.health = self.health.score(project, SystemTime::now());
+                // The walked modified time can also move a project in or out of the filter
+                self.reposition(idx);
+            }
+            ProjectEvent::Scanned | ProjectEvent::WatchFailed(_) => {}
+        }
+        Ok(())
+    }
+}
+
+impl Index<usize> for ProjectStore {
+    type Output = Project;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.projects[self.display_order[index]]
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Project {
+    pub(crate) name: String,
+    pub(crate) path: PathBuf,
+    /// Readme file found in the project, if any
+    pub(crate) readme_path: Option<PathBuf>,
+    /// Contents of `readme_path`, not kept when `lazy_readme` is set
+    pub(crate) readme: Option<String>,
+    /// Description from the project's `Cargo.toml` or `package.json`
+    pub(crate) description: Option<String>,
+    pub(crate) modified: std::time::SystemTime,
+    pub(crate) file_count: usize,
+    /// Walking stopped at `max_walk_files` so `file_count` and `size_bytes` are partial
+    pub(crate) file_count_truncated: bool,
+    /// Total size of the project's files, not counting ignored files
+    pub(crate) size_bytes: u64,
+    pub(crate) git: Option<GitStatus>,
+    pub(crate) kind: Option<ProjectKind>,
+    /// Whether CI is configured, counted towards the health score
+    pub(crate) has_ci: bool,
+    /// Settings from the project's own `.porgi.toml`
+    pub(crate) config: ProjectConfig,
+    /// Why the project's `.porgi.toml` couldn't be read, its settings are ignored
+    pub(crate) config_error: Option<String>,
+    /// Tags given to the project with (#), filled in when it is added to a `ProjectStore`
+    pub(crate) tags: Vec<String>,
+    /// Health score sorted by, filled in by the `ProjectStore` as the project is added,
+    /// walked or sorted
+    pub(crate) health: u8,
+    /// Index of the entry in `Config::project_dirs` the project was found in
+    pub(crate) root: usize,
+    /// Workspace this project is a member of when workspaces are expanded
+    pub(crate) parent: Option<ProjectKey>,
+}
+
+impl Project {
+    pub fn from_path(config: &Config, path: PathBuf, root: usize) -> Result<Self> {
+        let name = path
+            .file_name()
+            .ok_or_eyre("Project path does not have a name")?
+            .to_string_lossy()
+            .to_string();
+
+        let readme_path = find_readme(&path, &config.readme_files(), config.readme_case_sensitive);
+        // An unreadable readme is left out like `lazy_readme` leaves it out when shown
+        let readme = readme_path
+            .as_ref()
+            .filter(|_| !config.lazy_readme)
+            .and_then(|readme_path| std::fs::read_to_string(readme_path).ok());
+
+        let (modified, file_count) = (std::fs::metadata(path.as_path())?.modified()?, 0);
+
+        let git = GitStatus::from_path(&path);
+        let kind = ProjectKind::detect(&path, &config.project_markers);
+        let has_ci = health::has_ci(&path);
+        let description = manifest::description(&path);
+
+        // A broken project config shouldn't stop the project from being listed
+        let (config, config_error) = match ProjectConfig::load(&path) {
+            Ok(config) => (config.unwrap_or_default(), None),
+            Err(e) => (ProjectConfig::default(), Some(format!("{:#}", e))),
+        };
+
+        Ok(Project {
+            name,
+            path,
+            readme_path,
+            readme,
+            description,
+            modified,
+            file_count,
+            file_count_truncated: false,
+            size_bytes: 0,
+            git,
+            kind,
+            has_ci,
+            config,
+            config_error,
+            tags: Vec::new(),
+            health: 0,
+            root,
+            parent: None,
+        })
+    }
+
+    pub(crate) fn key(&self) -> &ProjectKey {
+        &self.path
+    }
+
+    /// Set the project directory's modified time to now, moving it to the top of the
+    /// modified sort order without changing any of its files
+    pub(crate) fn touch(&mut self) -> Result<()> {
+        let now = std::time::SystemTime::now();
+        filetime::set_file_mtime(&self.path, filetime::FileTime::from_system_time(now))
+            .wrap_err_with(|| format!("Failed to touch {}", self.path.display()))?;
+        self.modified = now;
+        Ok(())
+    }
+
+    pub(crate) fn is_git(&self) -> bool {
+        self.git.is_some()
+    }
+
+    /// Whether the project is a git repository with uncommitted changes
+    pub(crate) fn is_dirty(&self) -> bool {
+        self.git.as_ref().is_some_and(|git| git.dirty)
+    }
+
+    /// Submodules needing `git submodule update` or with changes of their own, if any
+    pub(crate) fn submodule_summary(&self) -> Option<String> {
+        self.git.as_ref().and_then(|git| git.submodules.summary())
+    }
+
+    /// Whether the project is a bare git repository, one without a working tree
+    pub(crate) fn is_bare(&self) -> bool {
+        self.git.as_ref().is_some_and(|git| git.bare)
+    }
+
+    /// Checked out branch if the project is a git repository
+    pub(crate) fn branch(&self) -> Option<&str> {
+        self.git.as_ref().and_then(|git| git.branch.as_deref())
+    }
+
+    /// Checked out branch, noting the default branch when a different one is checked out,
+    /// whether the repository is bare, whether HEAD is detached and commits not yet pushed
+    /// or pulled
+    pub(crate) fn branch_summary(&self) -> Option<String> {
+        let git = self.git.as_ref()?;
+        let branch = git.branch.as_deref()?;
+
+        let summary = if git.bare {
+            format!("{} (bare)", branch)
+        } else if git.detached {
+            format!("{} (detached)", branch)
+        } else {
+            match &git.default_branch {
+                Some(default) if git.off_default_branch() => {
+                    format!("{} (default {})", branch, default)
+                }
+                _ => branch.to_string(),
+            }
+        };
+
+        match git.sync_summary() {
+            Some(sync) => Some(format!("{} {}", summary, sync)),
+            None => Some(summary),
+        }
+    }
+
+    /// File count, followed by `+` when walking stopped before counting every file
+    pub(crate) fn format_file_count(&self) -> String {
+        if self.file_count_truncated {
+            format!("{}+", self.file_count)
+        } else {
+            self.file_count.to_string()
+        }
+    }
+
+    /// Size of the files walked, followed by `+` like `format_file_count`
+    pub(crate) fn format_size(&self) -> String {
+        if self.file_count_truncated {
+            format!("{}+", format_size(self.size_bytes))
+        } else {
+            format_size(self.size_bytes)
+        }
+    }
+
+    /// Time since the project was last modified, negative if its modified time is in the future
+    pub(crate) fn age(&self) -> TimeDelta {
+        let modified: DateTime<Local> = self.modified.into();
+        Local::now().signed_duration_since(modified)
+    }
+
+    /// Modified time as shown in the project list, relative to now unless `absolute` is set
+    pub(crate) fn format_modified(&self, absolute: bool) -> String {
+        let modified: DateTime<Local> = self.modified.into();
+
+        if absolute {
+            modified.format("%Y-%m-%d %H:%M").to_string()
+        } else {
+            format_relative(self.age(), modified)
+        }
+    }
+
+    /// `arg` with the placeholders in commands replaced: `{path}`, `{name}` and
+    /// `{readme_path}`, which is empty without a readme
+    pub(crate) fn expand_placeholders(&self, arg: &str) -> String {
+        let path = self.path.to_string_lossy();
+        let readme_path = self
+            .readme_path
+            .as_deref()
+            .map(Path::to_string_lossy)
+            .unwrap_or_default();
+        let values = [path.as_ref(), self.name.as_str(), readme_path.as_ref()];
+
+        // One pass over `arg`, so placeholders inside the values are left alone
+        let mut expanded = String::with_capacity(arg.len());
+        let mut rest = arg;
+        while let Some((start, placeholder, value)) = PLACEHOLDERS
+            .iter()
+            .zip(values)
+            .filter_map(|(placeholder, value)| {
+                rest.find(placeholder)
+                    .map(|start| (start, placeholder, value))
+            })
+            .min_by_key(|(start, _, _)| *start)
+        {
+            expanded.push_str(&rest[..start]);
+            expanded.push_str(value);
+            rest = &rest[start + placeholder.len()..];
+        }
+        expanded.push_str(rest);
+        expanded
+    }
+
+    /// Summarize the project as a markdown snippet suitable for pasting into notes or tickets
+    pub(crate) fn to_markdown(&self) -> String {
+        let modified: DateTime<Local> = self.modified.into();
+
+        let mut md = format!("### {}\n\n", self.name);
+        let _ = writeln!(md, "- **Path:** `{}`", self.path.display());
+        if let Some(description) = &self.description {
+            let _ = writeln!(md, "- **Description:** {}", description);
+        }
+        if let Some(branch) = self.branch() {
+            let _ = writeln!(md, "- **Branch:** `{}`", branch);
+        }
+        let _ = writeln!(
+            md,
+            "- **Last modified:** {}",
+            modified.format("%Y-%m-%d %H:%M")
+        );
+
+        md
+    }
+}
+
+/// `path` with the home directory shown as `~`, the reverse of the tilde expansion of
+/// configured paths
+pub(crate) fn abbreviate_home(path: &Path) -> String {
+    abbreviate(path, dirs::home_dir().as_deref())
+}
+
+fn abbreviate(path: &Path, home: Option<&Path>) -> String {
+    match home.and_then(|home| path.strip_prefix(home).ok()) {
+        Some(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+        Some(rest) => format!("~{}{}", std::path::MAIN_SEPARATOR, rest.display()),
+        None => path.display().to_string(),
+    }
+}
+
+/// Project at `path` with nothing found in it, for tests to fill in what they check
+#[cfg(test)]
+pub(crate) fn test_project(path: &str, modified: std::time::SystemTime) -> Project {
+    let path = PathBuf::from(path);
+    Project {
+        name: path.file_name().unwrap().to_string_lossy().to_string(),
+        path,
+        readme_path: None,
+        readme: None,
+        description: None,
+        modified,
+        file_count: 0,
+        file_count_truncated: false,
+        size_bytes: 0,
+        git: None,
+        kind: None,
+        has_ci: false,
+        config: ProjectConfig::default(),
+        config_error: None,
+        tags: Vec::new(),
+        health: 0,
+        root: 0,
+        parent: None,
+    }
+}
+
+/// Human readable size such as `1.2 GiB`
+pub(crate) fn format_size(bytes: u64) -> String {
+    humansize::format_size(bytes, humansize::BINARY.decimal_places(1))
+}
+
+fn format_relative(age: TimeDelta, modified: DateTime<Local>) -> String {
+    const PARTS: &[DurationPart] = &[
+        DurationPart::Days,
+        DurationPart::Hours,
+        DurationPart::Minutes,
+    ];
+
+    let recent = TimeDelta::minutes(1);
+    let relative_limit = TimeDelta::hours(48);
+
+    if age.abs() < recent {
+        "just now".to_string()
+    } else if age < -relative_limit {
+        // Far future times come from clock skew or restored backups, a date would
+        // look like a real time in the past at a glance
+        "in the future".to_string()
+    } else if age > relative_limit {
+        modified.format("%Y-%m-%d").to_string()
+    } else if age >= TimeDelta::zero() {
+        format!("{} ago", FancyDuration::new(age).filter(PARTS))
+    } else {
+        format!("{} from now", FancyDuration::new(age.abs()).filter(PARTS))
+    }
+}
+
+/// First of `candidates`, relative to the project at `path`, that is a file
+fn find_readme(path: &Path, candidates: &[&str], case_sensitive: bool) -> Option<PathBuf> {
+    candidates.iter().find_map(|candidate| {
+        let exact = path.join(candidate);
+        if exact.is_file() {
+            Some(exact)
+        } else if case_sensitive {
+            None
+        } else {
+            find_ignore_case(path, Path::new(candidate))
+        }
+    })
+}
+
+/// Resolve `relative` under `dir` comparing each component without regard to ASCII case
+fn find_ignore_case(dir: &Path, relative: &Path) -> Option<PathBuf> {
+    relative
+        .components()
+        .try_fold(dir.to_path_buf(), |dir, component| {
+            let name = component.as_os_str().to_str()?;
+            std::fs::read_dir(&dir)
+                .ok()?
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .find(|entry| {
+                    entry
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.eq_ignore_ascii_case(name))
+                })
+        })
+        .filter(|path| path.is_file())
+}
+
+/// Walk the project at `path`, stopping after `max_files` files if set
+fn get_file_summary(
+    walk: &WalkConfig,
+    max_files: Option<usize>,
+    path: &Path,
+) -> Result<FileSummary> {
+    let mut summary = FileSummary {
+        modified: std::fs::metadata(path)?.modified()?,
+        file_count: 0,
+        size_bytes: 0,
+        truncated: false,
+    };
+
+    let mut entries = WalkBuilder::new(path)
+        .git_ignore(walk.respect_gitignore)
+        .git_exclude(walk.respect_gitignore)
+        .git_global(walk.respect_git_global)
+        .ignore(walk.respect_ignore_files)
+        .parents(walk.respect_parent_ignores)
+        .hidden(walk.skip_hidden)
+        .build()
+        .filter_map(Result::ok)
+        .filter_map(|path| path.metadata().ok())
+        .filter(|metadata| metadata.modified().is_ok());
+
+    for metadata in entries.by_ref() {
+        if max_files == Some(summary.file_count) {
+            summary.truncated = true;
+            break;
+        }
+
+        summary.file_count += 1;
+        if metadata.is_file() {
+            summary.size_bytes += metadata.len();
+        }
+        if let Ok(modified) = metadata.modified() {
+            summary.modified = summary.modified.max(modified);
+        }
+    }
+
+    Ok(summary)
+}
+
+/// How long the watcher waits for changes to settle, so a build writing thousands of
+/// files rescans the project once rather than thousands of times
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Stream of the events of a scan, ending once every project has been found and walked
+/// unless the project directories are being watched for changes. Errors from the fetcher,
+/// walker or watcher are yielded as they finish, the others keep going.
+pub(crate) struct ProjectLoader {
+    config: Arc<Config>,
+    /// Where what walking found is kept between scans, see `ScanCache`
+    cache_path: Option<PathBuf>,
+    rx: tokio::sync::mpsc::Receiver<ProjectEvent>,
+    /// Taken once finished, as a finished `JoinHandle` must not be polled again
+    fetcher: Option<tokio::task::JoinHandle<Result<()>>>,
+    walker: Option<tokio::task::JoinHandle<Result<()>>>,
+    /// Kept across refreshes with the channel it sends on, so watches aren't installed
+    /// again every scan
+    watcher: Option<tokio::task::JoinHandle<Result<()>>>,
+    watch_rx: Option<Receiver<ProjectEvent>>,
+    finished: bool,
+}
+
+impl ProjectLoader {
+    pub(crate) fn new(config: Arc<Config>, cache_path: Option<PathBuf>) -> Result<Self> {
+        let (rx, fetcher, walker) = Self::spawn(config.clone(), cache_path.clone());
+        let (watcher, watch_rx) = if config.watch() {
+            let (watch_tx, watch_rx) = tokio::sync::mpsc::channel(100);
+            (
+                Some(Self::spawn_watcher(config.clone(), watch_tx)),
+                Some(watch_rx),
+            )
+        } else {
+            (None, None)
+        };
+
+        Ok(ProjectLoader {
+            config,
+            cache_path,
+            rx,
+            fetcher: Some(fetcher),
+            walker: Some(walker),
+            watcher,
+            watch_rx,
+            finished: false,
+        })
+    }
+
+    /// Scan the project directories again, abandoning any scan still in progress. The
+    /// watcher, if any, carries on.
+    pub(crate) fn refresh(&mut self) {
+        for task in [self.fetcher.take(), self.walker.take()]
+            .into_iter()
+            .flatten()
+        {
+            task.abort();
+        }
+
+        let (rx, fetcher, walker) = Self::spawn(self.config.clone(), self.cache_path.clone());
+        self.rx = rx;
+        self.fetcher = Some(fetcher);
+        self.walker = Some(walker);
+        self.finished = false;
+    }
+
+    /// Whether the scan has ended, until the next `refresh`
+    pub(crate) fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Poll the task in `slot`, taking it out once it has finished
+    fn poll_task(
+        slot: &mut Option<tokio::task::JoinHandle<Result<()>>>,
+        name: &str,
+        cx: &mut futures::task::Context,
+    ) -> Poll<Result<()>> {
+        let Some(task) = slot else {
+            return Poll::Ready(Ok(()));
+        };
+
+        let result = std::task::ready!(task.poll_unpin(cx));
+        *slot = None;
+        Poll::Ready(match result {
+            Ok(result) => result.wrap_err_with(|| format!("Project {} failed", name)),
+            Err(e) => Err(eyre::Report::new(e).wrap_err(format!("Project {} failed", name))),
+        })
+    }
+
+    /// Scan the project directories to completion, including walking every project, for
+    /// use without the TUI
+    pub(crate) async fn scan(config: Arc<Config>) -> Result<ProjectStore> {
+        let (mut rx, fetcher, walker) = Self::spawn(config, None);
+        let mut store = ProjectStore::new(SortKey::Name);
+
+        // Both tasks hold senders so the channel closes once they are done
+        while let Some(event) = rx.recv().await {
+            store.update(event)?;
+        }
+
+        fetcher.await??;
+        walker.await??;
+
+        Ok(store)
+    }
+
+    /// Start scanning. With a `cache_path` projects get what the last scan found as soon
+    /// as they are added, only sending updates when walking them again finds something
+    /// different.
+    fn spawn(
+        config: Arc<Config>,
+        cache_path: Option<PathBuf>,
+    ) -> (
+        Receiver<ProjectEvent>,
+        tokio::task::JoinHandle<Result<()>>,
+        tokio::task::JoinHandle<Result<()>>,
+    ) {
+        let (tx, rx) = tokio::sync::mpsc::channel(100);
+        let (walker_tx, walker_rx): (Sender<PathBuf>, Receiver<PathBuf>) =
+            tokio::sync::mpsc::channel(100);
+
+        let cached = Arc::new(
+            cache_path
+                .as_deref()
+                .map(ScanCache::load)
+                .unwrap_or_default(),
+        );
+        let walked = Arc::new(Mutex::new(ScanCache::default()));
+
+        let fetcher = tokio::spawn(
+            Self::fetcher(config.clone(), tx.clone(), walker_tx, cached.clone()).boxed(),
+        );
+        let walker_rx_stream = ReceiverStream::new(walker_rx);
+
+        let walker = tokio::spawn(async move {
+            walker_rx_stream
+                .map::<Result<PathBuf>, _>(Ok)
+                .try_for_each_concurrent(8, |path| {
+                    let config = config.clone();
+                    let tx = tx.clone();
+                    let cached = cached.clone();
+                    let walked = walked.clone();
+                    async move {
+                        let summary = Self::walk(config, path.clone()).await?;
+                        let unchanged = cached.get(&path) == Some(&summary);
+                        walked.lock().unwrap().insert(path.clone(), summary);
+                        if !unchanged {
+                            tx.send(ProjectEvent::Update(path, summary)).await?;
+                        }
+                        Ok(())
+                    }
+                })
+                .await?;
+
+            // Only projects walked by this scan are kept, dropping any since deleted. The
+            // cache only spares walking next time, so failing to write it doesn't fail
+            // the scan.
+            if let Some(cache_path) = cache_path {
+                let walked = std::mem::take(&mut *walked.lock().unwrap());
+                let _ = tokio::task::spawn_blocking(move || walked.save(&cache_path)).await;
+            }
+            tx.send(ProjectEvent::Scanned).await?;
+            Ok(())
+        });
+
+        (rx, fetcher, walker)
+    }
+
+    /// Count the files of the project at `path` and find when it was last modified
+    async fn walk(config: Arc<Config>, path: PathBuf) -> Result<FileSummary> {
+        tokio::task::spawn_blocking(move || {
+            let mut summary = get_file_summary(&config.walk, config.max_walk_files, &path)?;
+            // Projects without commits fall back to their files' times
+            if config.use_git_time {
+                if let Some(modified) = last_commit_time(&path) {
+                    summary.modified = modified;
+                }
+            }
+            Ok(summary)
+        })
+        .await?
+    }
+
+    pub(crate) async fn fetcher(
+        config: Arc<Config>,
+        tx: tokio::sync::mpsc::Sender<ProjectEvent>,
+        tx_walker: tokio::sync::mpsc::Sender<PathBuf>,
+        cached: Arc<ScanCache>,
+    ) -> Result<()> {
+        let project_dirs: Vec<PathBuf> = config
+            .project_dirs
+            .iter()
+            .map(ProjectDir::expanded_path)
+            .collect();
+
+        let depth = config.scan_depth();
+        let entries_stream = stream::iter(project_dirs.into_iter().enumerate())
+            .then(|(root, dir)| async move {
+                let paths =
+                    tokio::task::spawn_blocking(move || find_projects(&dir, depth)).await??;
+                Ok::<_, eyre::Report>(stream::iter(paths).map(move |path| Ok((root, path))))
+            })
+            .try_flatten();
+
+        let config = &config;
+        let tx = &tx;
+        let tx_walker = &tx_walker;
+        let cached = &cached;
+
+        entries_stream
+            .try_for_each_concurrent(8, |(root, path)| {
+                Self::add_project(config, tx, tx_walker, cached, root, path)
+            })
+            .await?;
+
+        // Listed projects that no longer exist are left out like deleted scanned ones
+        let listed_root = config.listed_root();
+        for path in config.listed_projects() {
+            if path.is_dir() {
+                Self::add_project(config, tx, tx_walker, cached, listed_root, path).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Send the project at `path` and any workspace members it has, with what the last
+    /// scan found walking them, then queue them for walking
+    async fn add_project(
+        config: &Config,
+        tx: &Sender<ProjectEvent>,
+        tx_walker: &Sender<PathBuf>,
+        cached: &ScanCache,
+        root: usize,
+        path: PathBuf,
+    ) -> Result<()> {
+        for project in Self::read_projects(config, root, &path)? {
+            let path = project.path.clone();
+            tx.send(ProjectEvent::Add(Box::new(project))).await?;
+            if let Some(summary) = cached.get(&path) {
+                tx.send(ProjectEvent::Update(path.clone(), *summary))
+                    .await?;
+            }
+            tx_walker.send(path).await?;
+        }
+        Ok(())
+    }
+
+    /// The project at `path` followed by its workspace members when they are expanded
+    fn read_projects(config: &Config, root: usize, path: &Path) -> Result<Vec<Project>> {
+        let mut projects = vec![Project::from_path(config, path.to_path_buf(), root)
+            .context("Failed to read project")?];
+
+        if config.expand_workspaces {
+            for member_path in workspace::members(path) {
+                let mut member = Project::from_path(config, member_path, root)
+                    .context("Failed to read workspace member")?;
+                member.parent = Some(path.to_path_buf());
+                projects.push(member);
+            }
+        }
+
+        Ok(projects)
+    }
+
+    /// Start a task rereading and walking projects whose files change, and adding projects
+    /// created in the project directories
+    fn spawn_watcher(
+        config: Arc<Config>,
+        tx: Sender<ProjectEvent>,
+    ) -> tokio::task::JoinHandle<Result<()>> {
+        tokio::spawn(async move {
+            // Installing the watches can take a while on large trees, so it isn't done
+            // before the first projects are shown
+            let watching = {
+                let config = config.clone();
+                tokio::task::spawn_blocking(move || Self::watch(&config)).await?
+            };
+            let (watches, changes) = match watching {
+                Ok(watching) => watching,
+                Err(e) => {
+                    tx.send(ProjectEvent::WatchFailed(format!("{:#}", e)))
+                        .await?;
+                    return Ok(());
+                }
+            };
+            Self::watcher(config, tx, watches, changes).await
+        })
+    }
+
+    fn watch(config: &Config) -> Result<(Watches, Receiver<DebounceEventResult>)> {
+        let (changes_tx, changes_rx) = tokio::sync::mpsc::channel(16);
+        let debouncer = new_debouncer(WATCH_DEBOUNCE, move |result| {
+            // Fails once the watcher task is gone, which drops the debouncer with it
+            let _ = changes_tx.blocking_send(result);
+        })
+        .wrap_err("Could not start watching for changes")?;
+
+        let mut watches = Watches {
+            debouncer,
+            dirs: HashSet::new(),
+        };
+        watches.watch_roots(config)?;
+        Ok((watches, changes_rx))
+    }
+
+    /// Reread and walk projects as their files change. Failures are sent as
+    /// `ProjectEvent::WatchFailed` and watching carries on.
+    async fn watcher(
+        config: Arc<Config>,
+        tx: Sender<ProjectEvent>,
+        // Watching stops when this is dropped
+        watches: Watches,
+        mut changes: Receiver<DebounceEventResult>,
+    ) -> Result<()> {
+        let watches = Arc::new(Mutex::new(watches));
+        // Some platforms report changes under the canonical path of a directory
+        let project_dirs: Vec<(PathBuf, PathBuf)> = config
+            .project_dirs
+            .iter()
+            .map(|dir| {
+                let path = dir.expanded_path();
+                let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+                (path, canonical)
+            })
+            .collect();
+
+        let depth = config.scan_depth();
+        while let Some(events) = changes.recv().await {
+            let events = match events {
+                Ok(events) => events,
+                Err(e) => {
+                    let e = eyre::Report::new(e).wrap_err("Could not watch for changes");
+                    tx.send(ProjectEvent::WatchFailed(format!("{:#}", e)))
+                        .await?;
+                    continue;
+                }
+            };
+
+            // Ignore files are read once per project for each batch of changes
+            let mut ignores: HashMap<PathBuf, Gitignore> = HashMap::new();
+            let mut changed: BTreeSet<(usize, PathBuf)> = BTreeSet::new();
+            // Directories created above the projects may hold new ones to watch
+            let mut above_projects = false;
+            for event in &events {
+                let Some((root, project, relative)) =
+                    changed_project(&project_dirs, depth, &event.path)
+                else {
+                    above_projects |= event.path.is_dir();
+                    continue;
+                };
+                let ignore = ignores
+                    .entry(project.clone())
+                    .or_insert_with(|| project_ignores(&config.walk, &project));
+                if !ignored_change(ignore, &project, &relative) {
+                    changed.insert((root, project));
+                }
+            }
+
+            // Directories created in the changed projects are watched too
+            let rewatch = {
+                let config = config.clone();
+                let watches = watches.clone();
+                let projects: Vec<PathBuf> = changed.iter().map(|(_, path)| path.clone()).collect();
+                tokio::task::spawn_blocking(move || {
+                    let mut watches = watches.lock().unwrap();
+                    if above_projects {
+                        watches.watch_roots(&config)?;
+                    }
+                    for project in projects.iter().filter(|project| project.is_dir()) {
+                        watches.watch_project(&config.walk, project)?;
+                    }
+                    Ok::<_, eyre::Report>(())
+                })
+                .await?
+            };
+            if let Err(e) = rewatch {
+                tx.send(ProjectEvent::WatchFailed(format!("{:#}", e)))
+                    .await?;
+            }
+
+            for (root, path) in changed {
+                // Files directly in a project directory aren't projects, and deleted
+                // projects are left for the next rescan to drop
+                if !path.is_dir() {
+                    continue;
+                }
+
+                if let Err(e) = Self::reread(&config, &tx, root, &path).await {
+                    let e = e.wrap_err(format!("Could not reread {}", path.display()));
+                    tx.send(ProjectEvent::WatchFailed(format!("{:#}", e)))
+                        .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Send the changed project at `path` and its workspace members again, walked
+    async fn reread(
+        config: &Arc<Config>,
+        tx: &Sender<ProjectEvent>,
+        root: usize,
+        path: &Path,
+    ) -> Result<()> {
+        for project in Self::read_projects(config, root, path)? {
+            let path = project.path.clone();
+            tx.send(ProjectEvent::Add(Box::new(project))).await?;
+            let summary = Self::walk(config.clone(), path.clone()).await?;
+            tx.send(ProjectEvent::Update(path, summary)).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Directories watched for changes, each without its subdirectories so those walking
+/// skips, such as `target` or `node_modules`, aren't watched
+struct Watches {
+    debouncer: Debouncer<RecommendedWatcher>,
+    dirs: HashSet<PathBuf>,
+}
+
+impl Watches {
+    /// Watch the project directories down to the projects, and projects not yet watched
+    fn watch_roots(&mut self, config: &Config) -> Result<()> {
+        for dir in config.project_dirs.iter().map(ProjectDir::expanded_path) {
+            // Missing project directories are reported by the fetcher
+            if dir.is_dir() {
+                self.watch_above_projects(&config.walk, &dir, config.scan_depth())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Watch `dir` and what is in it `depth` levels down, following `find_projects`
+    fn watch_above_projects(&mut self, walk: &WalkConfig, dir: &Path, depth: usize) -> Result<()> {
+        self.watch_dir(dir)?;
+        // Unreadable directories are left out like `find_projects` leaves them out
+        for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+
+            if depth <= 1 || path.join(".git").exists() {
+                if !self.dirs.contains(&path) {
+                    self.watch_project(walk, &path)?;
+                }
+            } else {
+                self.watch_above_projects(walk, &path, depth - 1)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Watch the directories of the project at `project` that walking it doesn't skip,
+    /// leaving out `.git`
+    fn watch_project(&mut self, walk: &WalkConfig, project: &Path) -> Result<()> {
+        // Directories deleted since are watched again if they come back
+        self.dirs
+            .retain(|dir| !dir.starts_with(project) || dir.is_dir());
+
+        let dirs = WalkBuilder::new(project)
+            .git_ignore(walk.respect_gitignore)
+            .git_exclude(walk.respect_gitignore)
+            .git_global(walk.respect_git_global)
+            .ignore(walk.respect_ignore_files)
+            .parents(walk.respect_parent_ignores)
+            .hidden(walk.skip_hidden)
+            .filter_entry(|entry| entry.file_name() != ".git")
+            .build()
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                entry
+                    .file_type()
+                    .is_some_and(|file_type| file_type.is_dir())
+            });
+
+        for entry in dirs {
+            self.watch_dir(entry.path())?;
+        }
+        Ok(())
+    }
+
+    fn watch_dir(&mut self, dir: &Path) -> Result<()> {
+        if self.dirs.contains(dir) {
+            return Ok(());
+        }
+
+        self.debouncer
+            .watcher()
+            .watch(dir, RecursiveMode::NonRecursive)
+            .wrap_err_with(|| format!("Could not watch {}", dir.display()))?;
+        self.dirs.insert(dir.to_path_buf());
+        Ok(())
+    }
+}
+
+/// Directories under `dir` that are projects: those `depth` levels down, and git
+/// repositories above that, which aren't looked inside
+fn find_projects(dir: &Path, depth: usize) -> io::Result<Vec<PathBuf>> {
+    let mut projects = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+
+        if depth <= 1 || path.join(".git").exists() {
+            projects.push(path);
+        } else {
+            // Only the project directory itself has to be readable
+            projects.extend(find_projects(&path, depth - 1).unwrap_or_default());
+        }
+    }
+    Ok(projects)
+}
+
+/// Whether scanning `dir` down to `depth` finds `path` as a project, as `find_projects`
+/// does
+pub(crate) fn scan_finds(dir: &Path, depth: usize, path: &Path) -> bool {
+    let dirs = [(dir.to_path_buf(), dir.to_path_buf())];
+    changed_project(&dirs, depth, path).is_some_and(|(_, project, _)| project == path)
+}
+
+/// Root and path of the project `path` is in, being in one of `project_dirs`, given as
+/// their path and canonical path, and at most `depth` levels down as `find_projects` finds
+/// them, along with `path` relative to the project. `None` for directories above the
+/// projects.
+fn changed_project(
+    project_dirs: &[(PathBuf, PathBuf)],
+    depth: usize,
+    path: &Path,
+) -> Option<(usize, PathBuf, PathBuf)> {
+    project_dirs
+        .iter()
+        .enumerate()
+        .find_map(|(root, (dir, canonical))| {
+            let relative = path
+                .strip_prefix(dir)
+                .or_else(|_| path.strip_prefix(canonical))
+                .ok()?;
+
+            let mut project = dir.clone();
+            let mut components = relative.components();
+            for level in 0..depth {
+                project.push(components.next()?);
+                if level + 1 == depth || project.join(".git").exists() {
+                    return Some((root, project, components.as_path().to_path_buf()));
+                }
+            }
+            None
+        })
+}
+
+/// Ignore files at the top of `project` that walking it respects
+fn project_ignores(walk: &WalkConfig, project: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(project);
+    // Missing or unparsable ignore files are left out like the walker leaves them out
+    if walk.respect_gitignore {
+        builder.add(project.join(".gitignore"));
+        builder.add(project.join(".git/info/exclude"));
+    }
+    if walk.respect_ignore_files {
+        builder.add(project.join(".ignore"));
+    }
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+/// Whether the change at `relative` in `project` is in `.git` or an ignored file, so a
+/// build or git command writing there doesn't reread and walk the project every time
+fn ignored_change(ignore: &Gitignore, project: &Path, relative: &Path) -> bool {
+    // The project directory itself being created or removed always counts
+    if relative.as_os_str().is_empty() {
+        return false;
+    }
+    if relative.components().any(|name| name.as_os_str() == ".git") {
+        return true;
+    }
+
+    // Deleted paths can't be told apart from files, their parents still match
+    let is_dir = project.join(relative).is_dir();
+    ignore
+        .matched_path_or_any_parents(relative, is_dir)
+        .is_ignore()
+}
+
+impl Stream for ProjectLoader {
+    type Item = Result<ProjectEvent>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut futures::task::Context,
+    ) -> Poll<Option<Self::Item>> {
+        let self_mut = self.get_mut();
+        if self_mut.finished {
+            return Poll::Ready(None);
+        }
+
+        // Events already sent are yielded before any error the tasks end with
+        let channel_closed = match self_mut.rx.poll_recv(cx) {
+            Poll::Ready(Some(event)) => return Poll::Ready(Some(Ok(event))),
+            Poll::Ready(None) => true,
+            Poll::Pending => false,
+        };
+
+        // The watcher's channel stays open across refreshes, and closes with the watcher
+        let watch_closed = match self_mut.watch_rx.as_mut().map(|rx| rx.poll_recv(cx)) {
+            Some(Poll::Ready(Some(event))) => return Poll::Ready(Some(Ok(event))),
+            Some(Poll::Ready(None)) | None => true,
+            Some(Poll::Pending) => false,
+        };
+
+        let fetcher = Self::poll_task(&mut self_mut.fetcher, "fetcher", cx);
+        let walker = Self::poll_task(&mut self_mut.walker, "walker", cx);
+        let watcher = Self::poll_task(&mut self_mut.watcher, "watcher", cx);
+        for task in [fetcher, walker, watcher] {
+            if let Poll::Ready(Err(e)) = task {
+                return Poll::Ready(Some(Err(e)));
+            }
+        }
+
+        // Every task holds a sender so the channels close once they are all done
+        if channel_closed
+            && watch_closed
+            && self_mut.fetcher.is_none()
+            && self_mut.walker.is_none()
+            && self_mut.watcher.is_none()
+        {
+            self_mut.finished = true;
+            return Poll::Ready(None);
+        }
+
+        Poll::Pending
+    }
+}
+
+/// Open `url` in the system's default browser without waiting for it to exit
+pub(crate) fn open_url(url: &str) -> Result<()> {
+    open::that_detached(url).wrap_err_with(|| format!("Failed to open {}", url))
+}
+
+/// Whether a command opener passes the project path after its `args`. Placeholders such
+/// as `{path}` in the args are replaced whichever is chosen.
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AddPathToArgs {
+    /// Append the path unless the args contain a placeholder
+    #[default]
+    Auto,
+    /// Always append the path as the final argument
+    Last,
+    /// Only pass the path where the args contain `{path}`
+    Never,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Command {
+    args: Vec<String>,
+    /// Environment variables for the command, overridden by the project's own `env`
+    #[serde(default)]
+    env: HashMap<String, String>,
+    #[serde(default = "ProjectOpener::chdir_default")]
+    chdir: bool,
+    #[serde(default)]
+    add_path_to_args: AddPathToArgs,
+    /// Wait for the command to exit before porgi carries on, as editors that run in the
+    /// terminal need. GUI apps and commands like `tmux new-window` can turn this off.
+    #[serde(default = "ProjectOpener::wait_default")]
+    wait: bool,
+}
+
+/// Placeholders replaced in command arguments by `Project::expand_placeholders`
+const PLACEHOLDERS: [&str; 3] = ["{path}", "{name}", "{readme_path}"];
+
+impl Command {
+    /// Arguments after the program to open `project` with, with placeholders replaced
+    fn args_for(&self, project: &Project) -> Vec<OsString> {
+        let configured = self.args.get(1..).unwrap_or_default();
+        let mut args: Vec<OsString> = configured
+            .iter()
+            .map(|arg| project.expand_placeholders(arg).into())
+            .collect();
+
+        let has_placeholder = configured.iter().any(|arg| {
+            PLACEHOLDERS
+                .iter()
+                .any(|placeholder| arg.contains(placeholder))
+        });
+        let append = match self.add_path_to_args {
+            AddPathToArgs::Auto => !has_placeholder,
+            AddPathToArgs::Last => true,
+            AddPathToArgs::Never => false,
+        };
+        if append {
+            args.push(project.path.clone().into());
+        }
+
+        args
+    }
+}
+
+/// Where a tmux opener puts the project
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TmuxMode {
+    /// A new window in the current session
+    #[default]
+    Window,
+    /// A session of its own, switched to if it already exists
+    Session,
+}
+
+/// Opens projects in tmux, named after the project and starting in its directory. When
+/// porgi isn't running inside tmux a session is attached in porgi's terminal instead.
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Tmux {
+    #[serde(default)]
+    mode: TmuxMode,
+}
+
+impl Tmux {
+    /// Window or session name for the project, tmux doesn't allow `.` or `:` in them
+    fn name(project: &Project) -> String {
+        project.name.replace(['.', ':'], "_")
+    }
+
+    /// Arguments to tmux creating the project's window or session, inside tmux a session
+    /// is created detached to be switched to
+    fn args(&self, project: &Project, inside_tmux: bool) -> Vec<OsString> {
+        let mut args: Vec<OsString> = match (inside_tmux, self.mode) {
+            (true, TmuxMode::Window) => vec!["new-window".into(), "-n".into()],
+            (true, TmuxMode::Session) => vec!["new-session".into(), "-d".into(), "-s".into()],
+            // Attaches to the session if it already exists
+            (false, _) => vec!["new-session".into(), "-A".into(), "-s".into()],
+        };
+        args.push(Self::name(project).into());
+        args.push("-c".into());
+        args.push(project.path.clone().into());
+
+        // The shell is started by the tmux server so it doesn't inherit porgi's env
+        let mut env: Vec<_> = project.config.env.iter().collect();
+        env.sort();
+        for (key, value) in env {
+            args.push("-e".into());
+            args.push(format!("{}={}", key, value).into());
+        }
+        args
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectOpener {
+    #[default]
+    Auto,
+    Code,
+    Editor,
+    Command(Command),
+    Tmux(Tmux),
+}
+
+impl ProjectOpener {
+    fn chdir_default() -> bool {
+        true
+    }
+
+    fn wait_default() -> bool {
+        true
+    }
+
+    pub(crate) async fn open(&self, project: &Project, retry: &SpawnRetry) -> Result<()> {
+        match self {
+            ProjectOpener::Auto => {
+                if which("code").is_ok() {
+                    Self::open_code(project, retry).await
+                } else if std::env::var("EDITOR").is_ok() {
+                    Self::open_editor(project, retry).await
+                } else {
+                    Err(anyhow!("vscode not found nor was an editor set"))
+                }
+            }
+            ProjectOpener::Code => Self::open_code(project, retry).await,
+            ProjectOpener::Editor => Self::open_editor(project, retry).await,
+            ProjectOpener::Command(cmd) => Self::open_command(project, cmd, retry).await,
+            ProjectOpener::Tmux(tmux) => Self::open_tmux(project, tmux, retry).await,
+        }
+    }
+
+    pub(crate) async fn open_tmux(
+        project: &Project,
+        tmux: &Tmux,
+        retry: &SpawnRetry,
+    ) -> Result<()> {
+        which("tmux")
+            .map_err(|_| anyhow!("tmux not found, install it or choose another opener"))?;
+        let inside_tmux = std::env::var_os("TMUX").is_some();
+
+        if inside_tmux && tmux.mode == TmuxMode::Session {
+            let target = format!("={}", Tmux::name(project));
+            let exists = process::Command::new("tmux")
+                .args(["has-session", "-t", &target])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .await?
+                .success();
+            if !exists {
+                let mut proc = process::Command::new("tmux");
+                proc.args(tmux.args(project, inside_tmux));
+                Self::run(&mut proc, retry, true).await?;
+            }
+
+            let mut proc = process::Command::new("tmux");
+            proc.args(["switch-client", "-t", &target]);
+            return Self::run(&mut proc, retry, true).await;
+        }
+
+        let mut proc = process::Command::new("tmux");
+        proc.args(tmux.args(project, inside_tmux));
+        // Outside tmux the session takes over the terminal until it is detached
+        Self::run(&mut proc, retry, !inside_tmux).await
+    }
+
+    pub(crate) async fn open_code(project: &Project, retry: &SpawnRetry) -> Result<()> {
+        let mut proc = process::Command::new("code");
+        proc.arg(&project.path).envs(&project.config.env);
+
+        // VS Code opens its own window so there is nothing to wait for
+        Self::run(&mut proc, retry, false).await
+    }
+
+    pub(crate) async fn open_editor(project: &Project, retry: &SpawnRetry) -> Result<()> {
+        let editor =
+            std::env::var("EDITOR").wrap_err("Could not read EDITOR environment variable")?;
+
+        let mut proc = process::Command::new(&editor);
+        proc.current_dir(&project.path)
+            .arg(&project.path)
+            .envs(&project.config.env);
+
+        // Terminal editors take over the terminal until they exit
+        Self::run(&mut proc, retry, true).await
+    }
+
+    pub(crate) async fn open_command(
+        project: &Project,
+        cmd: &Command,
+        retry: &SpawnRetry,
+    ) -> Result<()> {
+        let mut proc: process::Command = process::Command::new(&cmd.args[0]);
+
+        proc.args(cmd.args_for(project));
+
+        if cmd.chdir {
+            proc.current_dir(&project.path);
+        }
+
+        proc.envs(&cmd.env).envs(&project.config.env);
+
+        Self::run(&mut proc, retry, cmd.wait).await
+    }
+
+    /// Launch `proc`, only waiting for it to exit when `wait` is set so the project
+    /// list stays responsive while GUI openers run
+    async fn run(proc: &mut process::Command, retry: &SpawnRetry, wait: bool) -> Result<()> {
+        let mut child = retry.spawn(proc).await?;
+
+        if wait {
+            child.wait().await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// How often to retry launching an opener that failed to start, e.g. the first `code`
+/// launch after boot. Openers that start and then exit with an error are not retried.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SpawnRetry {
+    /// Retries after the first attempt
+    pub(crate) retries: u32,
+    /// Delay before the first retry, doubling for each one after
+    pub(crate) backoff: Duration,
+}
+
+impl SpawnRetry {
+    pub(crate) async fn spawn(&self, proc: &mut process::Command) -> io::Result<process::Child> {
+        let mut delay = self.backoff;
+        let mut attempt = 0;
+
+        loop {
+            match proc.spawn() {
+                Ok(child) => return Ok(child),
+                Err(e) if attempt >= self.retries => return Err(e),
+                Err(_) => {
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_dir;
+
+    const TEST_PROJECT_COUNT: usize = 1;
+
+    #[tokio::test]
+    async fn test_project_load() {
+        let config = Arc::new(Config {
+            project_dirs: vec![ProjectDir::new("tests/test_projects")],
+            ..Default::default()
+        });
+
+        let mut project_events = ProjectLoader::new(config.clone(), None).unwrap();
+        let mut project_store = ProjectStore::default();
+
+        let mut project_count = 0;
+
+        while let Some(event) = project_events.next().await.transpose().unwrap() {
+            project_store.update(event).unwrap();
+            project_count += 1;
+
+            if project_count >= TEST_PROJECT_COUNT {
+                break;
+            }
+        }
+
+        assert_eq!(project_store.len(), TEST_PROJECT_COUNT);
+        let project = project_store.projects.first().unwrap();
+        assert_eq!(project.name, "test_project_a");
+    }
+
+    #[tokio::test]
+    async fn test_loader_ends() {
+        let config = Arc::new(Config {
+            project_dirs: vec![ProjectDir::new("tests/test_projects")],
+            watch: Some(false),
+            ..Default::default()
+        });
+        let events: Vec<_> = ProjectLoader::new(config, None).unwrap().collect().await;
+        assert!(events.iter().all(Result::is_ok));
+        // The project is added and then walked, then the scan is done
+        assert!(events.len() >= 3);
+        assert!(matches!(events.last(), Some(Ok(ProjectEvent::Scanned))));
+
+        let config = Arc::new(Config {
+            project_dirs: vec![ProjectDir::new("tests/missing")],
+            watch: Some(false),
+            ..Default::default()
+        });
+        let mut loader = ProjectLoader::new(config, None).unwrap();
+        // Walking what little was found can finish before the failure is reported
+        let error = loop {
+            match loader.next().await {
+                Some(Ok(ProjectEvent::Scanned)) => {}
+                Some(Err(error)) => break error,
+                _ => panic!("missing project directory should fail the scan"),
+            }
+        };
+        assert!(error.to_string().contains("fetcher"));
+        assert!(loader.next().await.is_none());
+        assert!(loader.is_finished());
+    }
+
+    #[tokio::test]
+    async fn test_loader_cache() {
+        let cache_path = test_dir("loader-cache").join("scan.json");
+        let config = Arc::new(Config {
+            project_dirs: vec![ProjectDir::new("tests/test_projects")],
+            watch: Some(false),
+            ..Default::default()
+        });
+        let project = PathBuf::from("tests/test_projects/test_project_a");
+
+        let updates = |events: Vec<Result<ProjectEvent>>| {
+            events
+                .into_iter()
+                .filter_map(|event| match event.unwrap() {
+                    ProjectEvent::Update(key, summary) if key == project => Some(summary),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let loader = ProjectLoader::new(config.clone(), Some(cache_path.clone())).unwrap();
+        let walked = updates(loader.collect().await);
+        assert_eq!(walked.len(), 1);
+        assert_eq!(ScanCache::load(&cache_path).get(&project), walked.first());
+
+        // The cached summary is sent straight away and walking finds nothing new
+        let loader = ProjectLoader::new(config.clone(), Some(cache_path.clone())).unwrap();
+        assert_eq!(updates(loader.collect().await), walked);
+
+        // A cache that can't be written still finishes the scan
+        let unwritable = cache_path.join("scan.json");
+        let loader = ProjectLoader::new(config, Some(unwritable)).unwrap();
+        let events: Vec<_> = loader.collect().await;
+        assert!(matches!(events.last(), Some(Ok(ProjectEvent::Scanned))));
+        assert!(events.iter().all(Result::is_ok));
+
+        std::fs::remove_dir_all(cache_path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_watcher() {
+        let tmp = test_dir("watch");
+        std::fs::create_dir_all(tmp.join("existing")).unwrap();
+        std::fs::write(tmp.join("existing/notes.txt"), "a").unwrap();
+
+        let config = Arc::new(Config {
+            project_dirs: vec![ProjectDir::new(tmp.to_str().unwrap())],
+            watch: Some(true),
+            ..Default::default()
+        });
+        let mut loader = ProjectLoader::new(config, None).unwrap();
+
+        // Whether an event `matches` within `timeout`
+        async fn next_matching(
+            loader: &mut ProjectLoader,
+            timeout: Duration,
+            matches: impl Fn(&ProjectEvent) -> bool,
+        ) -> bool {
+            let found = async { while !matches(&loader.next().await.unwrap().unwrap()) {} };
+            tokio::time::timeout(timeout, found).await.is_ok()
+        }
+        // Wait for `path` to be added, or walked when `walked` is set
+        async fn wait_for(loader: &mut ProjectLoader, path: PathBuf, walked: bool) {
+            let found = next_matching(loader, Duration::from_secs(10), |event| match event {
+                ProjectEvent::Add(project) => !walked && project.path == path,
+                ProjectEvent::Update(key, _) => walked && *key == path,
+                _ => false,
+            });
+            assert!(found.await, "watcher should report {}", path.display());
+        }
+        let scanned = |event: &ProjectEvent| matches!(event, ProjectEvent::Scanned);
+
+        assert!(next_matching(&mut loader, Duration::from_secs(10), scanned).await);
+        // The watches are installed in the background, so change a file until they are
+        let existing = tmp.join("existing");
+        let walked = |event: &ProjectEvent| matches!(event, ProjectEvent::Update(key, _) if *key == existing);
+        let mut watching = false;
+        for _ in 0..10 {
+            std::fs::write(tmp.join("existing/notes.txt"), "changed").unwrap();
+            if next_matching(&mut loader, Duration::from_secs(1), walked).await {
+                watching = true;
+                break;
+            }
+        }
+        assert!(watching, "watcher should report the change");
+
+        std::fs::create_dir(tmp.join("created")).unwrap();
+        wait_for(&mut loader, tmp.join("created"), false).await;
+
+        // Directories created in a project are watched once it is reread
+        std::fs::create_dir(tmp.join("existing/src")).unwrap();
+        wait_for(&mut loader, tmp.join("existing"), true).await;
+        std::fs::write(tmp.join("existing/src/main.rs"), "fn main() {}").unwrap();
+        wait_for(&mut loader, tmp.join("existing"), true).await;
+
+        // Rescanning keeps the watcher going
+        loader.refresh();
+        assert!(next_matching(&mut loader, Duration::from_secs(10), scanned).await);
+        std::fs::create_dir(tmp.join("later")).unwrap();
+        wait_for(&mut loader, tmp.join("later"), false).await;
+
+        std::fs::remove_dir_all(&tmp).unwrap();
+    }
+
+    #[test]
+    fn test_watches_skip_ignored() {
+        let tmp = test_dir("watches");
+        std::fs::create_dir_all(tmp.join("org/repo/src")).unwrap();
+        std::fs::create_dir_all(tmp.join("org/repo/target/debug")).unwrap();
+        std::fs::create_dir_all(tmp.join("org/repo/.git")).unwrap();
+        std::fs::write(tmp.join("org/repo/.gitignore"), "target/\n").unwrap();
+
+        let config = Config {
+            project_dirs: vec![ProjectDir::new(tmp.to_str().unwrap())],
+            scan_depth: Some(2),
+            ..Default::default()
+        };
+        let (watches, _changes) = ProjectLoader::watch(&config).unwrap();
+        let mut watched: Vec<_> = watches.dirs.iter().cloned().collect();
+        watched.sort();
+        assert_eq!(
+            watched,
+            [
+                tmp.clone(),
+                tmp.join("org"),
+                tmp.join("org/repo"),
+                tmp.join("org/repo/src")
+            ]
+        );
+
+        std::fs::remove_dir_all(&tmp).unwrap();
+    }
+
+    #[test]
+    fn test_ignored_change() {
+        let tmp = test_dir("ignored");
+        std::fs::create_dir_all(tmp.join("target/debug")).unwrap();
+        std::fs::write(tmp.join(".gitignore"), "target/\n*.log\n").unwrap();
+
+        let ignore = project_ignores(&WalkConfig::default(), &tmp);
+        let ignored = |path: &str| ignored_change(&ignore, &tmp, Path::new(path));
+        assert!(ignored("target/debug/porgi"));
+        assert!(ignored("build.log"));
+        assert!(ignored(".git/index"));
+        assert!(!ignored("src/main.rs"));
+        assert!(!ignored(".gitignore"));
+        assert!(!ignored(""));
+
+        std::fs::remove_dir_all(&tmp).unwrap();
+    }
+
+    #[test]
+    fn test_scan_depth() {
+        let tmp = test_dir("depth");
+        std::fs::create_dir_all(tmp.join("org/repo")).unwrap();
+        std::fs::write(tmp.join("org/notes.txt"), "").unwrap();
+        std::fs::create_dir_all(tmp.join("shallow/.git")).unwrap();
+        std::fs::create_dir_all(tmp.join("shallow/src")).unwrap();
+        std::fs::create_dir_all(tmp.join("empty")).unwrap();
+
+        let found = |depth| {
+            let mut projects = find_projects(&tmp, depth).unwrap();
+            projects.sort();
+            projects
+        };
+        assert_eq!(
+            found(1),
+            [tmp.join("empty"), tmp.join("org"), tmp.join("shallow")]
+        );
+        // Git repositories are projects whatever level they are found at
+        assert_eq!(found(2), [tmp.join("org/repo"), tmp.join("shallow")]);
+
+        let dirs = [(tmp.clone(), tmp.clone())];
+        let changed = |path: &str| changed_project(&dirs, 2, &tmp.join(path)).map(|(_, p, _)| p);
+        assert_eq!(changed("org/repo/src/main.rs"), Some(tmp.join("org/repo")));
+        assert_eq!(changed("shallow/src/lib.rs"), Some(tmp.join("shallow")));
+        assert_eq!(changed("org"), None);
+
+        // Listed projects the scan finds are left to it, whatever level they are at
+        let config = Config {
+            project_dirs: vec![ProjectDir::new(tmp.to_str().unwrap())],
+            scan_depth: Some(2),
+            projects: ["org/repo", "org", "shallow", "shallow/src"]
+                .iter()
+                .map(|path| tmp.join(path).to_string_lossy().into_owned())
+                .collect(),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.listed_projects(),
+            [tmp.join("org"), tmp.join("shallow/src")]
+        );
+
+        std::fs::remove_dir_all(&tmp).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_listed_projects() {
+        let config = Arc::new(Config {
+            project_dirs: vec![ProjectDir::new("tests/test_projects")],
+            projects: vec![
+                "tests/test_workspaces/cargo_workspace".to_string(),
+                "tests/test_workspaces/cargo_workspace".to_string(),
+                "tests/test_projects/test_project_a".to_string(),
+                "tests/missing".to_string(),
+            ],
+            ..Default::default()
+        });
+
+        let store = ProjectLoader::scan(config.clone()).await.unwrap();
+        let mut projects: Vec<(&str, usize)> = store
+            .iter()
+            .map(|project| (project.name.as_str(), project.root))
+            .collect();
+        projects.sort();
+
+        assert_eq!(
+            projects,
+            vec![("cargo_workspace", 1), ("test_project_a", 0)]
+        );
+        assert_eq!(
+            config.roots().nth(1).unwrap().display_name(false),
+            "Listed projects"
+        );
+    }
+
+    #[test]
+    fn test_rescan_replaces_project() {
+        let now = std::time::SystemTime::now();
+        let mut store = ProjectStore::default();
+
+        let mut walked = test_project("/a/porgi", now);
+        walked.file_count = 42;
+        walked.size_bytes = 1024;
+        store.add(walked);
+
+        let mut rescanned = test_project("/a/porgi", now - std::time::Duration::from_secs(60));
+        rescanned.readme = Some("Updated".to_string());
+        store.add(rescanned);
+
+        assert_eq!(store.len(), 1);
+        assert_eq!(store[0].readme.as_deref(), Some("Updated"));
+        assert_eq!(store[0].file_count, 42);
+        assert_eq!(store[0].size_bytes, 1024);
+        assert_eq!(store.total_size(), 1024);
+        assert_eq!(store[0].modified, now);
+
+        store.remove_missing();
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_search_query() {
+        let now = std::time::SystemTime::now();
+        let mut store = ProjectStore::default();
+        store.add(test_project("/a/porgi", now));
+        let mut readme_project = test_project("/a/other", now);
+        readme_project.readme = Some("A corgi to organize projects".to_string());
+        store.add(readme_project);
+
+        store.set_query(SearchQuery::parse("PORG"));
+        assert_eq!(store.len(), 1);
+        assert_eq!(store[0].name, "porgi");
+
+        store.set_query(SearchQuery::parse("corgi"));
+        assert!(store.is_empty());
+
+        store.set_query(SearchQuery::parse("/corgi"));
+        assert_eq!(store.len(), 1);
+        assert_eq!(store[0].name, "other");
+
+        store.set_query(SearchQuery::default());
+        assert_eq!(store.len(), 2);
+    }
+
+    #[test]
+    fn test_tag_search() {
+        let now = std::time::SystemTime::now();
+        let mut store = ProjectStore::new(SortKey::Name).with_tags(Tags::load(None));
+        for name in ["porgi", "corgi", "other"] {
+            store.add(test_project(&format!("/a/{}", name), now));
+        }
+        store
+            .set_tags(&PathBuf::from("/a/porgi"), parse_tags("rust tui"))
+            .unwrap();
+        store
+            .set_tags(&PathBuf::from("/a/corgi"), parse_tags("Rust"))
+            .unwrap();
+
+        let names = |store: &ProjectStore| store.iter().map(|p| p.name.clone()).collect::<Vec<_>>();
+
+        store.set_query(SearchQuery::parse("tag:rust"));
+        assert_eq!(names(&store), ["corgi", "porgi"]);
+        store.set_query(SearchQuery::parse("tag:rust tag:tui"));
+        assert_eq!(names(&store), ["porgi"]);
+        store.set_query(SearchQuery::parse("cor tag:rust"));
+        assert_eq!(names(&store), ["corgi"]);
+
+        // Retagging a project moves it in or out of the search
+        store
+            .set_tags(&PathBuf::from("/a/other"), parse_tags("rust"))
+            .unwrap();
+        store.set_query(SearchQuery::parse("tag:rust"));
+        assert_eq!(names(&store), ["corgi", "other", "porgi"]);
+        assert_eq!(store[1].tags, ["rust"]);
+    }
+
+    #[test]
+    fn test_fuzzy_search() {
+        let now = std::time::SystemTime::now();
+        let mut store = ProjectStore::new(SortKey::Name);
+        for name in ["porgi", "project-organizer", "corgi"] {
+            store.add(test_project(&format!("/a/{}", name), now));
+        }
+
+        // Letters in order but not next to each other still match
+        store.set_query(SearchQuery::parse("prg"));
+        let names: Vec<_> = store.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["porgi", "project-organizer"]);
+
+        // The list keeps its order, the best match, here at the start of a word, is
+        // picked out of it
+        store.set_query(SearchQuery::parse("org"));
+        assert_eq!(store.len(), 3);
+        assert_eq!(
+            store.best_match().map(|i| store[i].name.as_str()),
+            Some("project-organizer")
+        );
+
+        // Equally good matches pick the first listed
+        store.set_query(SearchQuery::parse("gi"));
+        assert_eq!(store.best_match(), Some(0));
+
+        store.set_query(SearchQuery::parse("xyz"));
+        assert_eq!(store.best_match(), None);
+    }
+
+    #[test]
+    fn test_combined_filter() {
+        let now = std::time::SystemTime::now();
+        let old = now - std::time::Duration::from_secs(60 * 60 * 24 * 60);
+        let mut store = ProjectStore::default();
+
+        let mut porgi = test_project("/a/porgi", now);
+        porgi.git = Some(GitStatus::default());
+        porgi.kind = Some(ProjectKind::Rust);
+        store.add(porgi);
+
+        let mut stale = test_project("/a/stale", old);
+        stale.git = Some(GitStatus::default());
+        stale.kind = Some(ProjectKind::Rust);
+        store.add(stale);
+
+        let mut scratch = test_project("/a/scratch", now);
+        scratch.kind = Some(ProjectKind::Python);
+        store.add(scratch);
+
+        assert_eq!(store.kinds(), [ProjectKind::Python, ProjectKind::Rust]);
+
+        store.set_filter(Filter {
+            git_only: true,
+            kind: Some(ProjectKind::Rust),
+            ..Default::default()
+        });
+        assert_eq!(store.len(), 2);
+
+        store.set_filter(Filter {
+            git_only: true,
+            modified: filter::ModifiedFilter::OverAMonthAgo,
+            ..Default::default()
+        });
+        assert_eq!(store.len(), 1);
+        assert_eq!(store[0].name, "stale");
+
+        // Searching keeps the other predicates
+        store.set_query(SearchQuery::parse("porgi"));
+        assert!(store.is_empty());
+        assert!(store.filter().git_only);
+    }
+
+    #[test]
+    fn test_abbreviate_home() {
+        let home = Path::new("/home/corgi");
+
+        assert_eq!(abbreviate(Path::new("/home/corgi"), Some(home)), "~");
+        assert_eq!(
+            abbreviate(Path::new("/home/corgi/projects/porgi"), Some(home)),
+            format!(
+                "~{}projects{}porgi",
+                std::path::MAIN_SEPARATOR,
+                std::path::MAIN_SEPARATOR
+            )
+        );
+        // Only whole path components match
+        assert_eq!(
+            abbreviate(Path::new("/home/corgis/porgi"), Some(home)),
+            "/home/corgis/porgi"
+        );
+        assert_eq!(abbreviate(Path::new("/srv/porgi"), None), "/srv/porgi");
+    }
+
+    #[test]
+    fn test_find_readme() {
+        let project = Path::new("tests/test_projects/test_project_a");
+
+        assert_eq!(
+            find_readme(project, &["README.adoc", "readme.MD"], false),
+            Some(project.join("README.md"))
+        );
+        assert_eq!(find_readme(project, &["readme.MD"], true), None);
+        assert_eq!(find_readme(project, &["docs/README.md"], false), None);
+    }
+
+    #[test]
+    fn test_readme_not_utf8() {
+        let tmp = test_dir("readme");
+        std::fs::write(tmp.join("README"), b"caf\xe9").unwrap();
+
+        let project = Project::from_path(&Config::default(), tmp.clone(), 0).unwrap();
+        assert_eq!(project.readme_path, Some(tmp.join("README")));
+        assert_eq!(project.readme, None);
+
+        std::fs::remove_dir_all(&tmp).unwrap();
+    }
+
+    #[test]
+    fn test_sort_keeps_members_with_workspace() {
+        let now = std::time::SystemTime::now();
+        let earlier = now - std::time::Duration::from_secs(60);
+
+        let mut store = ProjectStore::new(SortKey::Modified);
+        store.add(test_project("/a/workspace", earlier));
+        store.add(test_project(
+            "/a/other",
+            now - std::time::Duration::from_secs(30),
+        ));
+        let mut member = test_project("/a/workspace/crates/member", now);
+        member.parent = Some(PathBuf::from("/a/workspace"));
+        store.add(member);
+        store.sort();
+
+        let names: Vec<_> = store.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["other", "workspace", "member"]);
+    }
+
+    #[test]
+    fn test_updates_keep_order_sorted() {
+        let now = std::time::SystemTime::now();
+        let mut store = ProjectStore::new(SortKey::Modified);
+        for i in 0..50 {
+            let project = test_project(&format!("/p/project-{}", i), now);
+            store.update(ProjectEvent::Add(Box::new(project))).unwrap();
+        }
+        let mut member = test_project("/p/project-3/member", now);
+        member.parent = Some(PathBuf::from("/p/project-3"));
+        store.update(ProjectEvent::Add(Box::new(member))).unwrap();
+
+        for i in 0..50 {
+            let modified = now + std::time::Duration::from_secs((i * 37) % 11);
+            let key = PathBuf::from(format!("/p/project-{}", (i * 7) % 50));
+            let summary = FileSummary {
+                modified,
+                file_count: 1,
+                size_bytes: 1,
+                truncated: false,
+            };
+            store.update(ProjectEvent::Update(key, summary)).unwrap();
+        }
+
+        let incremental: Vec<_> = store.iter().map(|p| p.path.clone()).collect();
+        store.sort();
+        let sorted: Vec<_> = store.iter().map(|p| p.path.clone()).collect();
+        assert_eq!(incremental, sorted);
+
+        // Members stay directly after their workspace as it moves
+        let workspace = store.position(&PathBuf::from("/p/project-3")).unwrap();
+        assert_eq!(store[workspace + 1].name, "member");
+
+        // Including once removing a project has shifted the rest
+        store.remove(&PathBuf::from("/p/project-0"));
+        let summary = FileSummary {
+            modified: now + std::time::Duration::from_secs(60),
+            file_count: 1,
+            size_bytes: 1,
+            truncated: false,
+        };
+        let key = PathBuf::from("/p/project-3");
+        store.update(ProjectEvent::Update(key, summary)).unwrap();
+        assert_eq!(store[0].name, "project-3");
+        assert_eq!(store[1].name, "member");
+    }
+
+    #[test]
+    fn test_sort_is_deterministic() {
+        let now = std::time::SystemTime::now();
+        let earlier = now - std::time::Duration::from_secs(60);
+
+        let paths = ["/b/same", "/a/same", "/a/zebra", "/a/apple"];
+
+        let sorted_names = |sort_key| {
+            let mut store = ProjectStore::new(sort_key);
+            for (i, path) in paths.iter().enumerate() {
+                let modified = if i == 3 { earlier } else { now };
+                store.add(test_project(path, modified));
+            }
+            store.sort();
+            store
+                .iter()
+                .map(|p| p.path.display().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            sorted_names(SortKey::Modified),
+            ["/a/same", "/b/same", "/a/zebra", "/a/apple"]
+        );
+        assert_eq!(
+            sorted_names(SortKey::Name),
+            ["/a/apple", "/a/same", "/b/same", "/a/zebra"]
+        );
+    }
+
+    #[test]
+    fn test_sort_by() {
+        let now = std::time::SystemTime::now();
+        let mut store = ProjectStore::new(SortKey::Modified);
+        for (name, file_count) in [("alpha", 1), ("zed", 100), ("mid", 10)] {
+            let mut project = test_project(&format!("/a/{}", name), now);
+            project.file_count = file_count;
+            store.add(project);
+        }
+
+        let names = |store: &ProjectStore| store.iter().map(|p| p.name.clone()).collect::<Vec<_>>();
+
+        store.sort_by(SortKey::FileCount);
+        assert_eq!(names(&store), ["zed", "mid", "alpha"]);
+        store.sort_by(SortKey::Name);
+        assert_eq!(names(&store), ["alpha", "mid", "zed"]);
+        assert_eq!(store.sort_key(), SortKey::Name);
+    }
+
+    #[test]
+    fn test_pinned_first() {
+        let now = std::time::SystemTime::now();
+        let mut store = ProjectStore::new(SortKey::Name).with_pins(Pins::load(None));
+        for name in ["alpha", "mid", "zed"] {
+            store.add(test_project(&format!("/a/{}", name), now));
+        }
+
+        let names = |store: &ProjectStore| store.iter().map(|p| p.name.clone()).collect::<Vec<_>>();
+
+        assert!(store.toggle_pin(&PathBuf::from("/a/zed")).unwrap());
+        assert!(store.toggle_pin(&PathBuf::from("/a/mid")).unwrap());
+        assert_eq!(names(&store), ["mid", "zed", "alpha"]);
+        assert!(store.is_pinned(&store[0]));
+
+        // Projects added later still go after the pinned ones
+        store.add(test_project("/a/beta", now));
+        assert_eq!(names(&store), ["mid", "zed", "alpha", "beta"]);
+
+        assert!(!store.toggle_pin(&PathBuf::from("/a/mid")).unwrap());
+        assert_eq!(names(&store), ["zed", "alpha", "beta", "mid"]);
+
+        // Members move with their workspace so can't be pinned on their own
+        let mut member = test_project("/a/alpha/member", now);
+        member.parent = Some(PathBuf::from("/a/alpha"));
+        store.add(member);
+        assert!(store.toggle_pin(&PathBuf::from("/a/alpha/member")).is_err());
+        assert!(!store.is_pinned(&store[2]));
+        assert_eq!(names(&store), ["zed", "alpha", "member", "beta", "mid"]);
+    }
+
+    #[test]
+    fn test_health() {
+        let now = std::time::SystemTime::now();
+        let old = now - std::time::Duration::from_secs(60 * 24 * 60 * 60);
+
+        let mut healthy = test_project("/a/healthy", now);
+        healthy.readme_path = Some(PathBuf::from("/a/healthy/README.md"));
+        healthy.has_ci = true;
+        let neglected = test_project("/a/neglected", old);
+
+        let health = HealthConfig::default();
+        assert_eq!(health.score(&healthy, now), 60);
+        assert_eq!(health.missing(&healthy, now), ["no git"]);
+        assert_eq!(health.score(&neglected, now), 0);
+        assert_eq!(
+            health.missing(&neglected, now),
+            ["no README", "no git", "not modified recently", "no CI"]
+        );
+
+        // Only CI counts
+        let ci_only: HealthConfig =
+            toml::from_str("readme = 0\ngit = 0\nclean = 0\nrecent = 0").unwrap();
+        assert_eq!(ci_only.score(&healthy, now), 100);
+        assert!(ci_only.missing(&healthy, now).is_empty());
+
+        let mut store = ProjectStore::new(SortKey::Health).with_health(health);
+        store.add(neglected);
+        store.add(healthy);
+        store.sort();
+        let names: Vec<_> = store.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["healthy", "neglected"]);
+        assert_eq!(store[0].health, 60);
+    }
+
+    #[test]
+    fn test_walk_config() {
+        let path = test_dir("walk");
+        std::fs::write(path.join(".ignore"), "build.log\n").unwrap();
+        std::fs::write(path.join("build.log"), "12345").unwrap();
+        std::fs::write(path.join("main.rs"), "1").unwrap();
+
+        // The project directory itself and main.rs
+        let summary = get_file_summary(&WalkConfig::default(), None, &path).unwrap();
+        assert_eq!((summary.file_count, summary.size_bytes), (2, 1));
+        assert!(!summary.truncated);
+
+        let walk = WalkConfig {
+            respect_ignore_files: false,
+            skip_hidden: false,
+            ..Default::default()
+        };
+        let summary = get_file_summary(&walk, None, &path).unwrap();
+        assert_eq!(summary.file_count, 4);
+
+        let summary = get_file_summary(&walk, Some(3), &path).unwrap();
+        assert_eq!(summary.file_count, 3);
+        assert!(summary.truncated);
+        let summary = get_file_summary(&walk, Some(4), &path).unwrap();
+        assert!(!summary.truncated);
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_project_opener() {
+        let path = test_dir("opener");
+        let config = Config {
+            project_dirs: vec![ProjectDir {
+                opener: Some(ProjectOpener::Editor),
+                ..ProjectDir::new("~")
+            }],
+            ..Default::default()
+        };
+
+        let project = Project::from_path(&config, path.clone(), 0).unwrap();
+        assert!(matches!(config.opener(&project), ProjectOpener::Editor));
+
+        std::fs::write(path.join(ProjectConfig::FILE_NAME), "opener = \"code\"\n").unwrap();
+        let project = Project::from_path(&config, path.clone(), 0).unwrap();
+        assert!(matches!(config.opener(&project), ProjectOpener::Code));
+        assert_eq!(project.config_error, None);
+
+        // A broken file is reported and the project still opens with the root's opener
+        std::fs::write(path.join(ProjectConfig::FILE_NAME), "opener = 1\n").unwrap();
+        let project = Project::from_path(&config, path.clone(), 0).unwrap();
+        assert!(matches!(config.opener(&project), ProjectOpener::Editor));
+        assert!(project.config_error.unwrap().contains(".porgi.toml:1:10"));
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_command_args() {
+        let args_for = |args: &[&str], add_path_to_args| {
+            let cmd = Command {
+                args: args.iter().map(|arg| arg.to_string()).collect(),
+                env: HashMap::new(),
+                chdir: true,
+                add_path_to_args,
+                wait: false,
+            };
+            let mut project = test_project("/p/porgi", std::time::SystemTime::now());
+            project.readme_path = Some(PathBuf::from("/p/porgi/README.md"));
+            cmd.args_for(&project)
+        };
+        let tmux = ["tmux", "new-window", "-c"];
+        let zed = ["zed", "--path={path}", "--wait"];
+
+        use AddPathToArgs::*;
+        assert_eq!(args_for(&tmux, Auto), ["new-window", "-c", "/p/porgi"]);
+        assert_eq!(args_for(&zed, Auto), ["--path=/p/porgi", "--wait"]);
+        assert_eq!(args_for(&tmux, Last), ["new-window", "-c", "/p/porgi"]);
+        assert_eq!(
+            args_for(&zed, Last),
+            ["--path=/p/porgi", "--wait", "/p/porgi"]
+        );
+        assert_eq!(args_for(&tmux, Never), ["new-window", "-c"]);
+        assert_eq!(args_for(&zed, Never), ["--path=/p/porgi", "--wait"]);
+
+        // Any placeholder stops the path being appended
+        assert_eq!(
+            args_for(&["tmux", "new-window", "-c", "/p", "-n", "{name}"], Auto),
+            ["new-window", "-c", "/p", "-n", "porgi"]
+        );
+        assert_eq!(
+            args_for(&["glow", "{readme_path}"], Auto),
+            ["/p/porgi/README.md"]
+        );
+
+        // Placeholders in the values themselves aren't replaced again
+        let mut project = test_project("/p/{name}", std::time::SystemTime::now());
+        project.name = "porgi".to_string();
+        assert_eq!(
+            project.expand_placeholders("{path} {name}"),
+            "/p/{name} porgi"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_spawn_retry() {
+        let retry = SpawnRetry {
+            retries: 2,
+            backoff: Duration::from_millis(10),
+        };
+
+        let start = std::time::Instant::now();
+        let mut missing = process::Command::new("porgi-test-no-such-program");
+        assert!(retry.spawn(&mut missing).await.is_err());
+        // Waited 10ms then 20ms before giving up
+        assert!(start.elapsed() >= Duration::from_millis(30));
+
+        let mut exists = process::Command::new(std::env::current_exe().unwrap());
+        exists.arg("--list").stdout(std::process::Stdio::null());
+        assert!(retry.spawn(&mut exists).await.unwrap().wait().await.is_ok());
+    }
+
+    #[test]
+    fn test_tmux_args() {
+        let mut project = test_project("/p/porgi.rs", std::time::SystemTime::now());
+        project
+            .config
+            .env
+            .insert("RUST_LOG".to_string(), "debug".to_string());
+        let args = |mode, inside_tmux| {
+            Tmux { mode }
+                .args(&project, inside_tmux)
+                .into_iter()
+                .map(|arg| arg.into_string().unwrap())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        assert_eq!(
+            args(TmuxMode::Window, true),
+            "new-window -n porgi_rs -c /p/porgi.rs -e RUST_LOG=debug"
+        );
+        assert_eq!(
+            args(TmuxMode::Session, true),
+            "new-session -d -s porgi_rs -c /p/porgi.rs -e RUST_LOG=debug"
+        );
+        assert_eq!(
+            args(TmuxMode::Window, false),
+            "new-session -A -s porgi_rs -c /p/porgi.rs -e RUST_LOG=debug"
+        );
+
+        let opener: ProjectOpener = toml::from_str::<Config>("[opener.tmux]\nmode = \"session\"")
+            .unwrap()
+            .opener;
+        assert!(matches!(
+            opener,
+            ProjectOpener::Tmux(Tmux {
+                mode: TmuxMode::Session
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_command_wait() {
+        let retry = SpawnRetry {
+            retries: 0,
+            backoff: Duration::ZERO,
+        };
+
+        // Commands opening a terminal editor, written before `wait` existed, still wait
+        let command: Command = toml::from_str("args = [\"nvim\"]").unwrap();
+        assert!(command.wait);
+
+        // Without waiting, a command reading stdin until it closes doesn't hold porgi up
+        let mut cat = process::Command::new("cat");
+        cat.stdin(Stdio::piped());
+        ProjectOpener::run(&mut cat, &retry, false).await.unwrap();
+
+        // Waiting, what the command does is done when it returns
+        let path = test_dir("wait").join("done");
+        let mut touch = process::Command::new("touch");
+        touch.arg(&path);
+        ProjectOpener::run(&mut touch, &retry, true).await.unwrap();
+        assert!(path.exists());
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_format_relative_future() {
+        let format = |age: TimeDelta| {
+            let modified = Local::now() - age;
+            format_relative(age, modified)
+        };
+
+        assert_eq!(format(-TimeDelta::seconds(30)), "just now");
+        assert_eq!(format(-TimeDelta::hours(2)), "2h from now");
+        assert_eq!(format(-TimeDelta::hours(47)), "1d 23h from now");
+        assert_eq!(format(-TimeDelta::hours(49)), "in the future");
+        assert_eq!(format(-TimeDelta::days(365 * 100)), "in the future");
+
+        // The past keeps switching to a date after the same threshold
+        assert_eq!(format(TimeDelta::hours(47)), "1d 23h ago");
+        assert_eq!(
+            format(TimeDelta::hours(49)),
+            (Local::now() - TimeDelta::hours(49))
+                .format("%Y-%m-%d")
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_project_stats() {
+        let hour_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(60 * 60);
+        let mut project = test_project("/projects/porgi", hour_ago);
+
+        assert!(!project.is_git());
+        assert_eq!(project.branch(), None);
+        assert_eq!(project.format_modified(false), "1h ago");
+
+        project.file_count = 10_000;
+        assert_eq!(project.format_file_count(), "10000");
+        project.size_bytes = 2048;
+        assert_eq!(project.format_size(), "2 KiB");
+        project.file_count_truncated = true;
+        assert_eq!(project.format_file_count(), "10000+");
+        assert_eq!(project.format_size(), "2 KiB+");
+
+        project.git = Some(GitStatus {
+            branch: Some("main".to_string()),
+            ..Default::default()
+        });
+        assert!(project.is_git());
+        assert_eq!(project.branch(), Some("main"));
+        assert_eq!(project.branch_summary().as_deref(), Some("main"));
+
+        project.git = Some(GitStatus {
+            branch: Some("feature".to_string()),
+            default_branch: Some("main".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            project.branch_summary().as_deref(),
+            Some("feature (default main)")
+        );
+
+        project.git = Some(GitStatus {
+            branch: Some("1a2b3c4".to_string()),
+            detached: true,
+            default_branch: Some("main".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            project.branch_summary().as_deref(),
+            Some("1a2b3c4 (detached)")
+        );
+
+        project.git = Some(GitStatus {
+            branch: Some("main".to_string()),
+            ahead_behind: Some((0, 3)),
+            ..Default::default()
+        });
+        assert_eq!(project.branch_summary().as_deref(), Some("main ↓3"));
+
+        let age = project.age();
+        assert!(age >= TimeDelta::hours(1) && age < TimeDelta::hours(1) + TimeDelta::minutes(1));
+    }
+
+    #[test]
+    fn test_project_to_markdown() {
+        let mut project = test_project("/projects/porgi", std::time::SystemTime::now());
+        project.git = Some(GitStatus {
+            branch: Some("main".to_string()),
+            ..Default::default()
+        });
+
+        assert!(!project.to_markdown().contains("**Description:**"));
+
+        project.description = Some("Organize your projects".to_string());
+        let md = project.to_markdown();
+
+        assert!(md.starts_with("### porgi\n\n"));
+        assert!(md.contains("- **Path:** `/projects/porgi`\n"));
+        assert!(md.contains("- **Description:** Organize your projects\n"));
+        assert!(md.contains("- **Branch:** `main`\n"));
+        assert!(md.contains("- **Last modified:** "));
+    }
+}
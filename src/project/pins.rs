@@ -0,0 +1,99 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use eyre::{Context, Result};
+
+/// Projects pinned to the top of the list, kept in a state file between runs
+#[derive(Debug, Default)]
+pub(crate) struct Pins {
+    /// File the pins are saved to whenever they change, not saved without one
+    path: Option<PathBuf>,
+    projects: HashSet<PathBuf>,
+}
+
+impl Pins {
+    /// Where pins are kept between runs
+    pub(crate) fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("porgi").join("pins.json"))
+    }
+
+    /// Read the pins saved at `path`, starting with none if it is missing or unreadable
+    pub(crate) fn load(path: Option<PathBuf>) -> Self {
+        let projects = path
+            .as_deref()
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+
+        Pins { path, projects }
+    }
+
+    pub(crate) fn contains(&self, project: &Path) -> bool {
+        self.projects.contains(project)
+    }
+
+    /// Pin `project` or unpin it if it already is, returning whether it is now pinned.
+    /// Nothing changes if the pins can't be saved.
+    pub(crate) fn toggle(&mut self, project: &Path) -> Result<bool> {
+        let pinned = !self.projects.remove(project);
+        if pinned {
+            self.projects.insert(project.to_path_buf());
+        }
+
+        if let Err(e) = self.save() {
+            if pinned {
+                self.projects.remove(project);
+            } else {
+                self.projects.insert(project.to_path_buf());
+            }
+            return Err(e);
+        }
+        Ok(pinned)
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Sorted so the file doesn't change order between saves
+        let projects: BTreeSet<&PathBuf> = self.projects.iter().collect();
+        fs::write(path, serde_json::to_vec_pretty(&projects)?)
+            .wrap_err_with(|| format!("Could not write pins {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_dir;
+
+    #[test]
+    fn test_pins() {
+        let tmp = test_dir("pins");
+        let path = tmp.join("state/pins.json");
+
+        let mut pins = Pins::load(Some(path.clone()));
+        assert!(pins.toggle(Path::new("/p/porgi")).unwrap());
+        assert!(pins.toggle(Path::new("/p/corgi")).unwrap());
+        assert!(!pins.toggle(Path::new("/p/corgi")).unwrap());
+
+        let loaded = Pins::load(Some(path.clone()));
+        assert!(loaded.contains(Path::new("/p/porgi")));
+        assert!(!loaded.contains(Path::new("/p/corgi")));
+
+        fs::write(&path, "[").unwrap();
+        assert!(Pins::load(Some(path)).projects.is_empty());
+
+        // Pins that can't be saved aren't kept either
+        let mut pins = Pins::load(Some(tmp.join("state/pins.json/pins.json")));
+        assert!(pins.toggle(Path::new("/p/porgi")).is_err());
+        assert!(!pins.contains(Path::new("/p/porgi")));
+
+        fs::remove_dir_all(&tmp).unwrap();
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::LazyLock;
+
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+
+use super::Project;
+
+/// Only the start of each readme is searched so huge readmes don't make typing sluggish
+const MAX_CONTENT_SEARCH_LEN: usize = 64 * 1024;
+
+static MATCHER: LazyLock<SkimMatcherV2> = LazyLock::new(SkimMatcherV2::default);
+
+/// Filter applied to the projects shown in the list
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct SearchQuery {
+    /// Lowercased text to look for
+    text: String,
+    /// Whether readmes are searched as well as names
+    pub(crate) content: bool,
+    /// Lowercased tags projects need all of, from `tag:` words
+    tags: Vec<String>,
+}
+
+impl SearchQuery {
+    /// Parse the text typed into the search bar. Starting it with `/` also searches readmes
+    /// and words like `tag:rust` only match projects with that tag.
+    pub(crate) fn parse(input: &str) -> Self {
+        let (input, content) = match input.strip_prefix('/') {
+            Some(input) => (input, true),
+            None => (input, false),
+        };
+
+        let mut tags = Vec::new();
+        let mut words = Vec::new();
+        for word in input.split(' ') {
+            match word.strip_prefix("tag:") {
+                Some(tag) if !tag.is_empty() => tags.push(tag.to_lowercase()),
+                // A lone `tag:` is still being typed
+                Some(_) => {}
+                None => words.push(word),
+            }
+        }
+
+        SearchQuery {
+            text: words.join(" ").to_lowercase(),
+            content,
+            tags,
+        }
+    }
+
+    /// How well the project's name fuzzy matches the query, higher is better. `None` when
+    /// the name doesn't match or there is no text to match.
+    pub(crate) fn name_score(&self, project: &Project) -> Option<i64> {
+        if self.text.is_empty() {
+            return None;
+        }
+        MATCHER.fuzzy_match(&project.name, &self.text)
+    }
+
+    pub(crate) fn matches(&self, project: &Project) -> bool {
+        let tagged = self.tags.iter().all(|tag| {
+            project
+                .tags
+                .iter()
+                .any(|project_tag| project_tag.to_lowercase() == *tag)
+        });
+        if !tagged {
+            return false;
+        }
+        if self.text.is_empty() || self.name_score(project).is_some() {
+            return true;
+        }
+
+        self.content
+            && project
+                .readme
+                .as_deref()
+                .is_some_and(|readme| truncate(readme).to_lowercase().contains(&self.text))
+    }
+}
+
+fn truncate(s: &str) -> &str {
+    let mut end = s.len().min(MAX_CONTENT_SEARCH_LEN);
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
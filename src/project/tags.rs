@@ -0,0 +1,111 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use eyre::{Context, Result};
+
+/// Tags given to projects, keyed by project path and kept in a state file between runs
+#[derive(Debug, Default)]
+pub(crate) struct Tags {
+    /// File the tags are saved to whenever they change, not saved without one
+    path: Option<PathBuf>,
+    tags: HashMap<PathBuf, Vec<String>>,
+}
+
+impl Tags {
+    /// Where tags are kept between runs
+    pub(crate) fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("porgi").join("tags.json"))
+    }
+
+    /// Read the tags saved at `path`, starting with none if it is missing or unreadable
+    pub(crate) fn load(path: Option<PathBuf>) -> Self {
+        let tags = path
+            .as_deref()
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+
+        Tags { path, tags }
+    }
+
+    pub(crate) fn get(&self, project: &Path) -> &[String] {
+        self.tags.get(project).map_or(&[], Vec::as_slice)
+    }
+
+    /// Replace the project's tags, removing them when `tags` is empty. Nothing changes if
+    /// the tags can't be saved.
+    pub(crate) fn set(&mut self, project: &Path, tags: Vec<String>) -> Result<()> {
+        let previous = if tags.is_empty() {
+            self.tags.remove(project)
+        } else {
+            self.tags.insert(project.to_path_buf(), tags)
+        };
+
+        if let Err(e) = self.save() {
+            match previous {
+                Some(previous) => self.tags.insert(project.to_path_buf(), previous),
+                None => self.tags.remove(project),
+            };
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Sorted so the file doesn't change order between saves
+        let tags: BTreeMap<&PathBuf, &Vec<String>> = self.tags.iter().collect();
+        fs::write(path, serde_json::to_vec_pretty(&tags)?)
+            .wrap_err_with(|| format!("Could not write tags {}", path.display()))
+    }
+}
+
+/// Tags typed into the tag prompt, separated by spaces or commas, without duplicates
+pub(crate) fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split([' ', ',']).filter(|tag| !tag.is_empty()) {
+        if !tags.iter().any(|existing| existing == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_dir;
+
+    #[test]
+    fn test_tags() {
+        let tmp = test_dir("tags");
+        let path = tmp.join("state/tags.json");
+
+        let mut tags = Tags::load(Some(path.clone()));
+        tags.set(Path::new("/p/porgi"), parse_tags("rust, tui rust"))
+            .unwrap();
+        tags.set(Path::new("/p/corgi"), parse_tags("dog")).unwrap();
+        tags.set(Path::new("/p/corgi"), parse_tags(" ")).unwrap();
+
+        let loaded = Tags::load(Some(path.clone()));
+        assert_eq!(loaded.get(Path::new("/p/porgi")), ["rust", "tui"]);
+        assert!(loaded.get(Path::new("/p/corgi")).is_empty());
+
+        fs::write(&path, "{").unwrap();
+        assert!(Tags::load(Some(path)).tags.is_empty());
+
+        // Tags that can't be saved aren't kept either
+        let mut tags = Tags::load(Some(tmp.join("state/tags.json/tags.json")));
+        assert!(tags.set(Path::new("/p/porgi"), parse_tags("rust")).is_err());
+        assert!(tags.get(Path::new("/p/porgi")).is_empty());
+
+        fs::remove_dir_all(&tmp).unwrap();
+    }
+}
@@ -0,0 +1,171 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use eyre::{bail, Context, Result};
+use ignore::WalkBuilder;
+
+use crate::config::WalkConfig;
+
+/// Where a project created from `template` goes when the user enters `input`, paths
+/// that aren't absolute are taken to be beside the template
+pub(crate) fn template_destination(template: &Path, input: &str) -> PathBuf {
+    let path = PathBuf::from(shellexpand::tilde(input.trim()).into_owned());
+    match template.parent() {
+        Some(parent) if path.is_relative() => parent.join(path),
+        _ => path,
+    }
+}
+
+/// Copy the project at `template` to `dest`, leaving out its `.git` directory so the
+/// new project doesn't start with the template's history, and whatever `walk` skips so
+/// build output isn't copied. Nothing is copied if `dest` already exists, and a partial
+/// copy is removed again.
+pub(crate) fn copy_template(template: &Path, dest: &Path, walk: &WalkConfig) -> Result<()> {
+    if dest.exists() {
+        bail!("{} already exists", dest.display());
+    }
+    // The copy would otherwise keep finding itself inside the template
+    if resolve(dest).starts_with(resolve(template)) {
+        bail!("Cannot create a project inside its template");
+    }
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Fails rather than merging into a directory created since the check above
+    fs::create_dir(dest).wrap_err_with(|| format!("Could not create {}", dest.display()))?;
+
+    copy_dir(template, dest, walk).map_err(|e| {
+        // Best effort, the copy error is the one worth reporting
+        let _ = fs::remove_dir_all(dest);
+        e.wrap_err(format!(
+            "Could not copy {} to {}",
+            template.display(),
+            dest.display()
+        ))
+    })
+}
+
+/// `path` with `..` and symlinks resolved as far as it exists, so it can be compared with
+/// other paths before it is created
+fn resolve(path: &Path) -> PathBuf {
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            // Already free of symlinks, so the parent is the one in the path
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            component => resolved.push(component),
+        }
+        if let Ok(canonical) = resolved.canonicalize() {
+            resolved = canonical;
+        }
+    }
+    resolved
+}
+
+fn copy_dir(from: &Path, to: &Path, walk: &WalkConfig) -> Result<()> {
+    // Hidden files are kept whatever `walk` says, dotfiles are part of a template
+    let entries = WalkBuilder::new(from)
+        .git_ignore(walk.respect_gitignore)
+        .git_exclude(walk.respect_gitignore)
+        .git_global(walk.respect_git_global)
+        .ignore(walk.respect_ignore_files)
+        .parents(walk.respect_parent_ignores)
+        .hidden(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build();
+
+    for entry in entries {
+        let entry = entry?;
+        let target = to.join(entry.path().strip_prefix(from)?);
+        let Some(file_type) = entry.file_type() else {
+            continue;
+        };
+
+        if entry.depth() == 0 {
+            continue;
+        } else if file_type.is_symlink() {
+            copy_symlink(entry.path(), &target)?;
+        } else if file_type.is_dir() {
+            fs::create_dir(&target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Recreate the link rather than copying what it points to, which may be a directory
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(from: &Path, to: &Path) -> Result<()> {
+    if from.is_dir() {
+        bail!("Cannot copy the directory link {}", from.display());
+    }
+    fs::copy(from, to)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_dir;
+
+    #[test]
+    fn test_copy_template() {
+        let tmp = test_dir("template");
+        let template = tmp.join("template");
+        fs::create_dir_all(template.join("src")).unwrap();
+        fs::create_dir_all(template.join(".git")).unwrap();
+        fs::write(template.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(template.join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(template.join(".ignore"), "target/\n").unwrap();
+        fs::create_dir_all(template.join("target/debug")).unwrap();
+        fs::write(template.join("target/debug/app"), "binary").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("src", template.join("source")).unwrap();
+
+        let dest = template_destination(&template, "new-project");
+        assert_eq!(dest, tmp.join("new-project"));
+
+        copy_template(&template, &dest, &WalkConfig::default()).unwrap();
+        assert_eq!(
+            fs::read_to_string(dest.join("src/main.rs")).unwrap(),
+            "fn main() {}"
+        );
+        assert!(dest.join("Cargo.toml").exists());
+        assert!(!dest.join(".git").exists());
+        assert!(dest.join(".ignore").exists());
+        assert!(!dest.join("target").exists());
+        #[cfg(unix)]
+        assert_eq!(
+            fs::read_link(dest.join("source")).unwrap(),
+            PathBuf::from("src")
+        );
+
+        // Copying again must not touch the existing project
+        fs::write(dest.join("Cargo.toml"), "changed").unwrap();
+        assert!(copy_template(&template, &dest, &WalkConfig::default()).is_err());
+        assert_eq!(
+            fs::read_to_string(dest.join("Cargo.toml")).unwrap(),
+            "changed"
+        );
+
+        // Going up and back into the template is still inside it
+        let name = tmp.file_name().unwrap().to_str().unwrap();
+        let inside = template_destination(&template, &format!("../{}/template/sub", name));
+        assert!(copy_template(&template, &inside, &WalkConfig::default()).is_err());
+        assert!(!template.join("sub").exists());
+
+        fs::remove_dir_all(&tmp).unwrap();
+    }
+}
@@ -0,0 +1,125 @@
+use std::path::{Component, Path, PathBuf};
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct CargoManifest {
+    workspace: Option<CargoWorkspace>,
+}
+
+#[derive(Deserialize)]
+struct CargoWorkspace {
+    #[serde(default)]
+    members: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct PackageJson {
+    workspaces: Option<JsWorkspaces>,
+}
+
+/// npm and yarn accept either a list of globs or an object with a `packages` list
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsWorkspaces {
+    Globs(Vec<String>),
+    Packages { packages: Vec<String> },
+}
+
+/// Directories of the members of a Cargo, npm/yarn or pnpm workspace rooted at `path`.
+///
+/// Unreadable or malformed manifests are treated as not being a workspace.
+pub(crate) fn members(path: &Path) -> Vec<PathBuf> {
+    let (patterns, exclude) = cargo_patterns(path)
+        .or_else(|| package_json_patterns(path))
+        .or_else(|| pnpm_patterns(path))
+        .unwrap_or_default();
+
+    let exclude: Vec<PathBuf> = exclude.iter().map(|p| path.join(p)).collect();
+
+    let mut members: Vec<PathBuf> = patterns
+        .iter()
+        // Members outside the workspace could clash with projects found elsewhere
+        .filter(|pattern| {
+            Path::new(pattern)
+                .components()
+                .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        })
+        .filter_map(|pattern| glob::glob(&path.join(pattern).to_string_lossy()).ok())
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|member| member.is_dir() && member != path && !exclude.contains(member))
+        .collect();
+
+    members.sort();
+    members.dedup();
+    members
+}
+
+fn cargo_patterns(path: &Path) -> Option<(Vec<String>, Vec<String>)> {
+    let manifest = std::fs::read_to_string(path.join("Cargo.toml")).ok()?;
+    let workspace = toml::from_str::<CargoManifest>(&manifest).ok()?.workspace?;
+    Some((workspace.members, workspace.exclude))
+}
+
+fn package_json_patterns(path: &Path) -> Option<(Vec<String>, Vec<String>)> {
+    let manifest = std::fs::read_to_string(path.join("package.json")).ok()?;
+    let patterns = match serde_json::from_str::<PackageJson>(&manifest)
+        .ok()?
+        .workspaces?
+    {
+        JsWorkspaces::Globs(globs) => globs,
+        JsWorkspaces::Packages { packages } => packages,
+    };
+    Some(split_negated(patterns))
+}
+
+/// Reads the `packages` list from `pnpm-workspace.yaml`. Only the simple block list
+/// form is understood which covers how these files are almost always written.
+fn pnpm_patterns(path: &Path) -> Option<(Vec<String>, Vec<String>)> {
+    let manifest = std::fs::read_to_string(path.join("pnpm-workspace.yaml")).ok()?;
+
+    let patterns = manifest
+        .lines()
+        .skip_while(|line| line.trim_end() != "packages:")
+        .skip(1)
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map_while(|line| line.strip_prefix('-'))
+        .map(|item| item.trim().trim_matches(['\'', '"']).to_string())
+        .collect();
+
+    Some(split_negated(patterns))
+}
+
+/// JS workspaces exclude directories with `!` prefixed patterns
+fn split_negated(patterns: Vec<String>) -> (Vec<String>, Vec<String>) {
+    let (exclude, include): (Vec<String>, Vec<String>) =
+        patterns.into_iter().partition(|p| p.starts_with('!'));
+    let exclude = exclude.iter().map(|p| p[1..].to_string()).collect();
+    (include, exclude)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cargo_workspace_members() {
+        let members = members(Path::new("tests/test_workspaces/cargo_workspace"));
+
+        let names: Vec<_> = members
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+
+        assert_eq!(names, ["member_a", "member_b"]);
+    }
+
+    #[test]
+    fn test_not_a_workspace() {
+        assert!(members(Path::new("tests/test_projects/test_project_a")).is_empty());
+    }
+}
@@ -0,0 +1,215 @@
+//! Plain text and markdown reports of every project, for running porgi without the TUI
+
+use std::fmt::Write;
+
+use chrono::{DateTime, Local, TimeDelta};
+
+use crate::{
+    config::Config,
+    project::{format_size, Project, ProjectStore},
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum ReportFormat {
+    #[default]
+    Text,
+    Markdown,
+}
+
+/// Render a report of the projects in `store` grouped by the project directory they were
+/// found in, flagging dirty repositories and projects not modified within `stale_after`,
+/// followed by the `largest` biggest projects
+pub(crate) fn render(
+    config: &Config,
+    store: &ProjectStore,
+    stale_after: TimeDelta,
+    largest: usize,
+    format: ReportFormat,
+) -> String {
+    let is_stale = |project: &Project| project.age() > stale_after;
+    let projects: Vec<&Project> = store.iter().collect();
+
+    let summary = |projects: &[&Project]| {
+        format!(
+            "projects: {}, dirty: {}, stale: {}, size: {}",
+            projects.len(),
+            projects.iter().filter(|p| p.is_dirty()).count(),
+            projects.iter().filter(|p| is_stale(p)).count(),
+            format_size(projects.iter().map(|p| p.size_bytes).sum()),
+        )
+    };
+
+    let mut out = String::new();
+
+    let title = format!(
+        "porgi report: {} (stale after {} days)",
+        summary(&projects),
+        stale_after.num_days()
+    );
+    match format {
+        ReportFormat::Text => writeln!(out, "{}", title),
+        ReportFormat::Markdown => writeln!(out, "# {}", title),
+    }
+    .unwrap();
+
+    for (root, dir) in config.roots().enumerate() {
+        let in_root: Vec<&Project> = projects
+            .iter()
+            .copied()
+            .filter(|project| project.root == root)
+            .collect();
+
+        if in_root.is_empty() {
+            continue;
+        }
+
+        let heading = format!(
+            "{} ({})",
+            dir.display_name(config.full_paths),
+            summary(&in_root)
+        );
+        match format {
+            ReportFormat::Text => writeln!(out, "\n{}", heading),
+            ReportFormat::Markdown => writeln!(out, "\n## {}\n", heading),
+        }
+        .unwrap();
+
+        for project in in_root {
+            let mut flags = Vec::new();
+            if project.is_dirty() {
+                flags.push("dirty");
+            }
+            if is_stale(project) {
+                flags.push("stale");
+            }
+            if project.submodule_summary().is_some() {
+                flags.push("submodules");
+            }
+
+            let modified: DateTime<Local> = project.modified.into();
+            let indent = if project.parent.is_some() { "  " } else { "" };
+            let flags = if flags.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", flags.join(", "))
+            };
+
+            match format {
+                ReportFormat::Text => writeln!(
+                    out,
+                    "  {:<32} {}{}",
+                    format!("{}{}", indent, project.name),
+                    modified.format("%Y-%m-%d"),
+                    flags
+                ),
+                ReportFormat::Markdown => writeln!(
+                    out,
+                    "{}- **{}** {}{}",
+                    indent,
+                    project.name,
+                    modified.format("%Y-%m-%d"),
+                    flags
+                ),
+            }
+            .unwrap();
+        }
+    }
+
+    let mut by_size = projects;
+    by_size.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then(a.path.cmp(&b.path)));
+    by_size.truncate(largest);
+
+    if !by_size.is_empty() {
+        match format {
+            ReportFormat::Text => writeln!(out, "\nLargest projects"),
+            ReportFormat::Markdown => writeln!(out, "\n## Largest projects\n"),
+        }
+        .unwrap();
+
+        for project in by_size {
+            let size = project.format_size();
+            let path = config.display_path(&project.path);
+            match format {
+                ReportFormat::Text => {
+                    writeln!(out, "  {:<32} {:>10}  {}", project.name, size, path)
+                }
+                ReportFormat::Markdown => {
+                    writeln!(out, "- **{}** {} `{}`", project.name, size, path)
+                }
+            }
+            .unwrap();
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+    use crate::config::ProjectDir;
+    use crate::project::{test_project, GitStatus, SortKey};
+
+    fn project(path: &str, root: usize, age_days: u64, dirty: bool, size_bytes: u64) -> Project {
+        let modified = SystemTime::now() - Duration::from_secs(age_days * 24 * 60 * 60);
+        Project {
+            size_bytes,
+            git: Some(GitStatus {
+                dirty,
+                ..Default::default()
+            }),
+            root,
+            ..test_project(path, modified)
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let config = Config {
+            project_dirs: vec![ProjectDir::new("~/src"), ProjectDir::new("~/empty")],
+            ..Default::default()
+        };
+
+        let mut store = ProjectStore::new(SortKey::Name);
+        store.add(project("/src/zebra", 0, 1, true, 3 * 1024 * 1024));
+        store.add(project("/src/apple", 0, 200, false, 1024));
+        store.add(project("/src/mango", 0, 1, false, 0));
+        store.sort();
+
+        let report = render(&config, &store, TimeDelta::days(90), 2, ReportFormat::Text);
+        let lines: Vec<&str> = report.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "porgi report: projects: 3, dirty: 1, stale: 1, size: 3.0 MiB (stale after 90 days)"
+        );
+        assert_eq!(
+            lines[2],
+            "~/src (projects: 3, dirty: 1, stale: 1, size: 3.0 MiB)"
+        );
+        assert!(lines[3].trim_start().starts_with("apple"));
+        assert!(lines[3].ends_with("[stale]"));
+        assert!(lines[4].trim_start().starts_with("mango"));
+        assert!(lines[5].ends_with("[dirty]"));
+        assert!(!report.contains("~/empty"));
+
+        assert_eq!(lines[7], "Largest projects");
+        assert!(lines[8].trim_start().starts_with("zebra"));
+        assert!(lines[9].trim_start().starts_with("apple"));
+        assert_eq!(lines.len(), 10);
+
+        let markdown = render(
+            &config,
+            &store,
+            TimeDelta::days(90),
+            2,
+            ReportFormat::Markdown,
+        );
+        assert!(markdown.starts_with("# porgi report"));
+        assert!(markdown.contains(
+            "\n## ~/src (projects: 3, dirty: 1, stale: 1, size: 3.0 MiB)\n\n- **apple** "
+        ));
+    }
+}
@@ -0,0 +1,9 @@
+use std::path::PathBuf;
+
+/// Scratch directory for a test, removed first in case a previous run left it behind
+pub(crate) fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("porgi-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
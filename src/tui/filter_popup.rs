@@ -0,0 +1,173 @@
+use crossterm::event::KeyCode;
+use ratatui::{prelude::*, widgets::*};
+
+use crate::{
+    config::Config,
+    project::{Filter, ProjectKind, SearchQuery},
+};
+
+/// Rows of the popup in display order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FilterRow {
+    Search,
+    GitOnly,
+    DirtyOnly,
+    Kind,
+    Modified,
+}
+
+const ROWS: [FilterRow; 5] = [
+    FilterRow::Search,
+    FilterRow::GitOnly,
+    FilterRow::DirtyOnly,
+    FilterRow::Kind,
+    FilterRow::Modified,
+];
+
+/// What the popup wants done after a key press
+pub(crate) enum FilterPopupOutcome {
+    Editing,
+    Apply,
+    Cancel,
+}
+
+/// Popup for editing every filter predicate at once, nothing changes until it is applied
+pub(crate) struct FilterPopup {
+    filter: Filter,
+    /// Search text as typed, parsed into the filter's query when applied
+    search_input: String,
+    /// Kinds to cycle through, those of the loaded projects
+    kinds: Vec<ProjectKind>,
+    row: usize,
+}
+
+impl FilterPopup {
+    pub(crate) fn new(filter: Filter, search_input: String, kinds: Vec<ProjectKind>) -> Self {
+        Self {
+            filter,
+            search_input,
+            kinds,
+            row: 0,
+        }
+    }
+
+    /// The edited filter and the search text it was built from
+    pub(crate) fn into_filter(self) -> (Filter, String) {
+        let mut filter = self.filter;
+        filter.query = SearchQuery::parse(&self.search_input);
+        (filter, self.search_input)
+    }
+
+    pub(crate) fn handle_input(&mut self, code: KeyCode) -> FilterPopupOutcome {
+        let row = ROWS[self.row];
+
+        match code {
+            KeyCode::Esc => return FilterPopupOutcome::Cancel,
+            KeyCode::Enter => return FilterPopupOutcome::Apply,
+            KeyCode::Up | KeyCode::BackTab => self.row = (self.row + ROWS.len() - 1) % ROWS.len(),
+            KeyCode::Down | KeyCode::Tab => self.row = (self.row + 1) % ROWS.len(),
+            KeyCode::Delete => {
+                self.filter = Filter::default();
+                self.search_input.clear();
+            }
+            KeyCode::Backspace if row == FilterRow::Search => {
+                self.search_input.pop();
+            }
+            KeyCode::Char(c) if row == FilterRow::Search => self.search_input.push(c),
+            KeyCode::Char(' ') | KeyCode::Right => self.change(row, true),
+            KeyCode::Left => self.change(row, false),
+            _ => {}
+        }
+
+        FilterPopupOutcome::Editing
+    }
+
+    /// Toggle or cycle the value of `row`
+    fn change(&mut self, row: FilterRow, forward: bool) {
+        match row {
+            FilterRow::Search => {}
+            FilterRow::GitOnly => self.filter.git_only = !self.filter.git_only,
+            FilterRow::DirtyOnly => self.filter.dirty_only = !self.filter.dirty_only,
+            FilterRow::Kind => {
+                // Any kind comes before the first kind
+                let idx = match &self.filter.kind {
+                    Some(kind) => self
+                        .kinds
+                        .iter()
+                        .position(|k| k == kind)
+                        .map_or(0, |i| i + 1),
+                    None => 0,
+                };
+                let len = self.kinds.len() + 1;
+                let next = (if forward { idx + 1 } else { idx + len - 1 }) % len;
+                self.filter.kind = next.checked_sub(1).map(|i| self.kinds[i].clone());
+            }
+            FilterRow::Modified => self.filter.modified = self.filter.modified.cycle(forward),
+        }
+    }
+
+    pub(crate) fn render(&self, config: &Config, area: Rect, buf: &mut Buffer) {
+        let area = centered(area, 50, ROWS.len() as u16 + 2);
+
+        let rows = ROWS.iter().enumerate().map(|(idx, row)| {
+            let (label, value) = match row {
+                FilterRow::Search => {
+                    let cursor = if idx == self.row { "▏" } else { "" };
+                    ("Search", format!("{}{}", self.search_input, cursor))
+                }
+                FilterRow::GitOnly => ("Git only", checkbox(self.filter.git_only)),
+                FilterRow::DirtyOnly => ("Dirty only", checkbox(self.filter.dirty_only)),
+                FilterRow::Kind => (
+                    "Kind",
+                    format!(
+                        "‹ {} ›",
+                        self.filter
+                            .kind
+                            .as_ref()
+                            .map_or("any".to_string(), ToString::to_string)
+                    ),
+                ),
+                FilterRow::Modified => ("Modified", format!("‹ {} ›", self.filter.modified)),
+            };
+
+            let line = Line::from(vec![
+                Span::raw(format!("{:<12}", label)).bold(),
+                Span::raw(value),
+            ]);
+
+            if idx == self.row {
+                line.fg(config.colors.selected_style_fg).reversed()
+            } else {
+                line
+            }
+        });
+
+        Clear.render(area, buf);
+        Paragraph::new(rows.collect::<Vec<_>>())
+            .block(
+                Block::bordered()
+                    .title("Filter")
+                    .title_alignment(Alignment::Center)
+                    .border_style(Style::new().fg(config.colors.footer_border_color)),
+            )
+            .fg(config.colors.text_color)
+            .bg(config.colors.normal_row_color)
+            .render(area, buf);
+    }
+}
+
+fn checkbox(checked: bool) -> String {
+    if checked { "[x]" } else { "[ ]" }.to_string()
+}
+
+/// Rect of at most `width` by `height` in the middle of `area`
+pub(super) fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
@@ -0,0 +1,82 @@
+use ratatui::{prelude::*, widgets::*};
+
+use super::filter_popup::centered;
+use crate::config::Config;
+
+/// Keys that can't be rebound and what they do, those that can are added by `render`
+const KEYS: &[(&str, &str)] = &[
+    ("gg", "Go to the top"),
+    ("O", "Open every shown project"),
+    ("c", "Copy project info"),
+    ("y", "Copy project path"),
+    ("C", "Copy shown project paths"),
+    ("w", "Open in a web editor"),
+    ("b", "Browse the origin remote"),
+    ("i", "Open the issue tracker"),
+    ("t", "Open a terminal"),
+    ("m", "Mark to compare READMEs"),
+    ("N", "New project from template"),
+    ("p", "Pin to the top"),
+    ("#", "Edit tags, search them with tag:name"),
+    ("u", "Touch, marking as modified now"),
+    ("dd", "Hide until the next rescan"),
+    ("D", "Toggle the diff"),
+    ("/", "Search, // to include READMEs"),
+    ("f", "Filter"),
+    ("s", "Change the sort order"),
+    ("T", "Toggle absolute times"),
+    ("Tab", "Switch layout"),
+    ("< / >", "Resize the panes"),
+    ("?", "Toggle this help"),
+];
+
+/// Popup listing every key, drawn over the middle of `area`
+pub(crate) fn render(config: &Config, area: Rect, buf: &mut Buffer) {
+    let bindings = &config.keybindings;
+    let mut keys: Vec<(String, &str)> = vec![(config.quit_label(), "Quit")];
+    if !config.esc_quits() {
+        keys.push(("esc".to_string(), "Clear the search and filter"));
+    }
+    if config.cd_on_exit {
+        keys.push(("enter".to_string(), "Quit into the project's directory"));
+    }
+    keys.extend([
+        (bindings.up.to_string(), "Move up"),
+        (bindings.down.to_string(), "Move down"),
+        (bindings.top.to_string(), "Go to the top"),
+        (bindings.bottom.to_string(), "Go to the bottom"),
+        (bindings.unselect.to_string(), "Unselect"),
+        (bindings.open.to_string(), "Open project"),
+    ]);
+    keys.extend(KEYS.iter().map(|(key, action)| (key.to_string(), *action)));
+
+    // Every key's chars are a column wide each, so padding by chars lines actions up
+    let key_width = keys
+        .iter()
+        .map(|(key, _)| key.chars().count())
+        .max()
+        .unwrap_or(0);
+    let lines: Vec<Line> = keys
+        .iter()
+        .map(|(key, action)| {
+            Line::from(vec![
+                Span::raw(format!(" {:<width$}  ", key, width = key_width)).bold(),
+                Span::raw(*action),
+            ])
+        })
+        .collect();
+
+    let area = centered(area, 50, lines.len() as u16 + 2);
+    Clear.render(area, buf);
+    Paragraph::new(lines)
+        .block(
+            Block::bordered()
+                .title("Keys")
+                .title_alignment(Alignment::Center)
+                .title_bottom(Line::from("(?/Esc) close").centered())
+                .border_style(Style::new().fg(config.colors.footer_border_color)),
+        )
+        .fg(config.colors.text_color)
+        .bg(config.colors.normal_row_color)
+        .render(area, buf);
+}
@@ -0,0 +1,180 @@
+use std::path::{Path, PathBuf};
+
+use image::DynamicImage;
+use ratatui::{prelude::*, widgets::StatefulWidget};
+use ratatui_image::{
+    picker::{Picker, ProtocolType},
+    protocol::StatefulProtocol,
+    Resize, StatefulImage,
+};
+
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Background;
+use crate::project::{Project, ProjectKey};
+
+/// Files checked, in order, for an image to show when the readme has none
+const IMAGE_FILES: &[&str] = &["logo.png", "icon.png", "logo.jpg", "icon.jpg"];
+
+/// Maximum number of rows of the info pane given over to the image
+const MAX_IMAGE_HEIGHT: u16 = 12;
+
+/// Renders a project's logo in the info pane on terminals supporting a graphics protocol
+pub(crate) struct ImagePreview {
+    picker: Picker,
+    /// Image for the most recently rendered project, only decoded when the selection
+    /// changes. `None` while it is being decoded or if the project has none.
+    current: Option<(ProjectKey, Option<Box<dyn StatefulProtocol>>)>,
+}
+
+impl ImagePreview {
+    /// Query the terminal for a graphics protocol, returning `None` if it only supports text.
+    ///
+    /// This writes to and reads from the terminal so must be called before input handling starts.
+    pub(crate) fn detect() -> Option<Self> {
+        let mut picker = Self::picker()?;
+
+        if picker.guess_protocol() == ProtocolType::Halfblocks {
+            return None;
+        }
+
+        Some(ImagePreview {
+            picker,
+            current: None,
+        })
+    }
+
+    #[cfg(unix)]
+    fn picker() -> Option<Picker> {
+        Picker::from_termios().ok()
+    }
+
+    #[cfg(not(unix))]
+    fn picker() -> Option<Picker> {
+        None
+    }
+
+    /// Render the image for `project` at the top of `area` returning the area left for other
+    /// content. A newly selected project's image is decoded off the UI task, sent back
+    /// through `background` and shown once it arrives.
+    pub(crate) fn render(
+        &mut self,
+        project: &Project,
+        readme: Option<&str>,
+        background: &UnboundedSender<Background>,
+        area: Rect,
+        buf: &mut Buffer,
+    ) -> Rect {
+        if self.current.as_ref().map(|(key, _)| key) != Some(project.key()) {
+            let key = project.key().clone();
+            if let Some(path) = find_image(project, readme) {
+                let background = background.clone();
+                let key = key.clone();
+                tokio::task::spawn_blocking(move || {
+                    let image = image::open(path).ok();
+                    // Fails only once the app is gone
+                    let _ = background.send(Background::Image(key, image));
+                });
+            }
+            self.current = Some((key, None));
+        }
+
+        let Some((_, Some(image))) = &mut self.current else {
+            return area;
+        };
+
+        let [image_area, rest] = Layout::vertical([
+            Constraint::Length(MAX_IMAGE_HEIGHT.min(area.height / 3)),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+
+        StatefulImage::new(None)
+            .resize(Resize::Fit(None))
+            .render(image_area, buf, image);
+
+        rest
+    }
+
+    /// Show the image decoded for `key` if it is still the project being shown
+    pub(crate) fn loaded(&mut self, key: ProjectKey, image: Option<DynamicImage>) {
+        if let Some((current, protocol @ None)) = &mut self.current {
+            if *current == key {
+                *protocol = image.map(|image| self.picker.new_resize_protocol(image));
+            }
+        }
+    }
+}
+
+fn find_image(project: &Project, readme: Option<&str>) -> Option<PathBuf> {
+    // Readmes come from cloned repositories, so their images mustn't lead out of them
+    let root = project.path.canonicalize().ok()?;
+    let readme_image = readme
+        .and_then(first_markdown_image)
+        .map(|src| project.path.join(src));
+
+    readme_image
+        .into_iter()
+        .chain(IMAGE_FILES.iter().map(|name| project.path.join(name)))
+        .filter_map(|path| path.canonicalize().ok())
+        .find(|path| path.starts_with(&root) && path.is_file())
+}
+
+/// Source of the first markdown image in `readme` if it is a relative path
+fn first_markdown_image(readme: &str) -> Option<&Path> {
+    let start = readme.find("![")?;
+    let rest = &readme[start..];
+    let src_start = rest.find("](")? + 2;
+    let src_end = src_start + rest[src_start..].find(')')?;
+    // Strip an optional title, `![alt](src "title")`
+    let src = rest[src_start..src_end].split_whitespace().next()?;
+
+    if src.contains("://") {
+        None
+    } else {
+        Some(Path::new(src))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::test_project;
+    use crate::test_util::test_dir;
+
+    #[test]
+    fn test_first_markdown_image() {
+        assert_eq!(
+            first_markdown_image("# Title\n![logo](docs/logo.png \"Logo\")\n"),
+            Some(Path::new("docs/logo.png"))
+        );
+        assert_eq!(
+            first_markdown_image("![badge](https://example.com/badge.svg)"),
+            None
+        );
+        assert_eq!(first_markdown_image("No images here"), None);
+    }
+
+    #[test]
+    fn test_find_image_stays_in_project() {
+        let dir = test_dir("find-image");
+        std::fs::create_dir_all(dir.join("project/docs")).unwrap();
+        std::fs::write(dir.join("project/docs/logo.png"), "").unwrap();
+        std::fs::write(dir.join("outside.png"), "").unwrap();
+        let project = test_project(
+            dir.join("project").to_str().unwrap(),
+            std::time::SystemTime::UNIX_EPOCH,
+        );
+
+        let found = |readme: &str| find_image(&project, Some(readme));
+        assert_eq!(
+            found("![logo](docs/logo.png)"),
+            Some(dir.join("project/docs/logo.png").canonicalize().unwrap())
+        );
+        assert_eq!(found("![logo](../outside.png)"), None);
+        let absolute = format!("![logo]({})", dir.join("outside.png").display());
+        assert_eq!(found(&absolute), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,164 @@
+use std::time::SystemTime;
+
+use crate::{config::Config, project::Project};
+
+/// Parts of the info pane, shown in the order configured by `info_sections`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum InfoSection {
+    /// Name, kind and project directory
+    Summary,
+    /// Branch and uncommitted changes
+    Git,
+    /// File count, size and last modified time
+    Stats,
+    /// Health score and the signals missing from it
+    Health,
+    /// One line description from `Cargo.toml` or `package.json`
+    Description,
+    /// The README, or the diff when it is toggled on
+    Readme,
+}
+
+const DEFAULT_SECTIONS: [InfoSection; 6] = [
+    InfoSection::Summary,
+    InfoSection::Git,
+    InfoSection::Stats,
+    InfoSection::Health,
+    InfoSection::Description,
+    InfoSection::Readme,
+];
+
+impl InfoSection {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "summary" => Some(InfoSection::Summary),
+            "git" => Some(InfoSection::Git),
+            "stats" => Some(InfoSection::Stats),
+            "health" => Some(InfoSection::Health),
+            "description" => Some(InfoSection::Description),
+            "readme" => Some(InfoSection::Readme),
+            _ => None,
+        }
+    }
+
+    /// Sections named in `names`, or the default sections when there are none, along
+    /// with the names that aren't sections
+    pub(crate) fn parse(names: &[String]) -> (Vec<Self>, Vec<&str>) {
+        if names.is_empty() {
+            return (DEFAULT_SECTIONS.to_vec(), Vec::new());
+        }
+
+        let mut sections = Vec::new();
+        let mut unknown = Vec::new();
+        for name in names {
+            match Self::from_name(name) {
+                Some(section) => sections.push(section),
+                None => unknown.push(name.as_str()),
+            }
+        }
+        (sections, unknown)
+    }
+
+    /// Text of the section for `project`, `None` when there is nothing to show
+    pub(crate) fn text(
+        self,
+        config: &Config,
+        project: &Project,
+        readme: Option<&str>,
+        diff: Option<&str>,
+    ) -> Option<String> {
+        match self {
+            InfoSection::Summary => {
+                let mut details = vec![project.name.clone()];
+                if let Some(kind) = &project.kind {
+                    details.push(kind.to_string());
+                }
+                details.push(
+                    config
+                        .root(project)
+                        .display_name(config.full_paths)
+                        .to_string(),
+                );
+                let mut summary = details.join(" · ");
+                if !project.tags.is_empty() {
+                    summary.push_str(&format!("\ntags: {}", project.tags.join(", ")));
+                }
+                if let Some(error) = &project.config_error {
+                    summary.push_str(&format!("\n{}, ignoring it", error));
+                }
+                Some(summary)
+            }
+            InfoSection::Git => {
+                let git = project.git.as_ref()?;
+                let changes = if git.dirty {
+                    format!(" ({})", git.changes.summary())
+                } else {
+                    String::new()
+                };
+                let submodules = project
+                    .submodule_summary()
+                    .map(|summary| format!(" · {}", summary))
+                    .unwrap_or_default();
+                let remote = git
+                    .display_remote()
+                    .map(|remote| format!("\nremote: {}", remote))
+                    .unwrap_or_default();
+                Some(format!(
+                    "git: {}{}{}{}",
+                    project
+                        .branch_summary()
+                        .unwrap_or_else(|| "no branch".to_string()),
+                    changes,
+                    submodules,
+                    remote
+                ))
+            }
+            InfoSection::Stats => Some(format!(
+                "files: {} · size: {} · modified: {}",
+                project.format_file_count(),
+                project.format_size(),
+                project.format_modified(false)
+            )),
+            InfoSection::Health => {
+                let now = SystemTime::now();
+                let missing = config.health.missing(project, now);
+                let score = config.health.score(project, now);
+                if missing.is_empty() {
+                    Some(format!("health: {}/100", score))
+                } else {
+                    Some(format!("health: {}/100 · {}", score, missing.join(", ")))
+                }
+            }
+            InfoSection::Description => project.description.clone(),
+            // A readme with nothing in it would otherwise look the same as having no readme
+            InfoSection::Readme => match (diff, readme) {
+                (Some(diff), _) => Some(diff.to_string()),
+                (None, Some(readme)) if readme.trim().is_empty() => {
+                    Some("(empty README)".to_string())
+                }
+                (None, Some(readme)) => Some(readme.to_string()),
+                (None, None) if project.is_bare() => {
+                    Some("(bare repository, no working tree)".to_string())
+                }
+                (None, None) => None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let (sections, unknown) = InfoSection::parse(&[]);
+        assert_eq!(sections, DEFAULT_SECTIONS);
+        assert!(unknown.is_empty());
+
+        let names = ["readme", "recent_files", "git"].map(String::from);
+        let (sections, unknown) = InfoSection::parse(&names);
+        assert_eq!(sections, vec![InfoSection::Readme, InfoSection::Git]);
+        assert_eq!(unknown, vec!["recent_files"]);
+    }
+}
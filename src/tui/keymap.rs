@@ -0,0 +1,266 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use eyre::{bail, eyre, Report};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Something a key can be bound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Action {
+    Quit,
+    Open,
+    Up,
+    Down,
+    Top,
+    Bottom,
+    Unselect,
+}
+
+/// Keys bound to each action, those left out of the config keep their default. Each
+/// action takes a key or a list of keys such as `"q"`, `"G"`, `"ctrl-c"` or `"home"`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeyMap {
+    /// Single keys replacing `quit_key` when set
+    pub(crate) quit: Option<Keys>,
+    pub(crate) open: Keys,
+    pub(crate) up: Keys,
+    pub(crate) down: Keys,
+    /// `gg` goes to the top as well
+    pub(crate) top: Keys,
+    pub(crate) bottom: Keys,
+    /// Runs `left_action`
+    pub(crate) unselect: Keys,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let keys = |keys: &[&str]| Keys(keys.iter().map(|key| key.parse().unwrap()).collect());
+        Self {
+            quit: None,
+            open: keys(&["o"]),
+            up: keys(&["k", "up"]),
+            down: keys(&["j", "down"]),
+            top: keys(&["home"]),
+            bottom: keys(&["G", "end"]),
+            unselect: keys(&["h", "left"]),
+        }
+    }
+}
+
+impl KeyMap {
+    /// Action `key` is bound to, quitting only when `quit` is set
+    pub(crate) fn action(&self, key: &KeyEvent) -> Option<Action> {
+        let bindings = [
+            (Action::Open, Some(&self.open)),
+            (Action::Up, Some(&self.up)),
+            (Action::Down, Some(&self.down)),
+            (Action::Top, Some(&self.top)),
+            (Action::Bottom, Some(&self.bottom)),
+            (Action::Unselect, Some(&self.unselect)),
+            (Action::Quit, self.quit.as_ref()),
+        ];
+
+        bindings
+            .into_iter()
+            .find(|(_, keys)| keys.is_some_and(|keys| keys.matches(key)))
+            .map(|(action, _)| action)
+    }
+}
+
+/// Keys bound to one action
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Keys(Vec<Key>);
+
+impl Keys {
+    pub(crate) fn matches(&self, event: &KeyEvent) -> bool {
+        self.0.iter().any(|key| key.matches(event))
+    }
+
+    pub(crate) fn contains(&self, code: KeyCode) -> bool {
+        self.0
+            .iter()
+            .any(|key| key.code == code && key.modifiers.is_empty())
+    }
+}
+
+impl fmt::Display for Keys {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let labels: Vec<String> = self.0.iter().map(ToString::to_string).collect();
+        f.write_str(&labels.join("/"))
+    }
+}
+
+impl<'de> Deserialize<'de> for Keys {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum OneOrMany {
+            One(String),
+            Many(Vec<String>),
+        }
+
+        let names = match OneOrMany::deserialize(deserializer)? {
+            OneOrMany::One(name) => vec![name],
+            OneOrMany::Many(names) => names,
+        };
+        names
+            .iter()
+            .map(|name| name.parse().map_err(serde::de::Error::custom))
+            .collect::<Result<_, _>>()
+            .map(Keys)
+    }
+}
+
+impl Serialize for Keys {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(ToString::to_string))
+    }
+}
+
+/// Names of keys other than characters, as written in the config
+const NAMED_KEYS: &[(&str, KeyCode)] = &[
+    ("esc", KeyCode::Esc),
+    ("enter", KeyCode::Enter),
+    ("tab", KeyCode::Tab),
+    ("backtab", KeyCode::BackTab),
+    ("space", KeyCode::Char(' ')),
+    ("backspace", KeyCode::Backspace),
+    ("delete", KeyCode::Delete),
+    ("insert", KeyCode::Insert),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+];
+
+/// A key with the ctrl and alt modifiers it is pressed with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    /// Shift isn't compared as it is part of the character for letters and symbols
+    fn matches(&self, event: &KeyEvent) -> bool {
+        let modifiers = event.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        self.code == event.code && self.modifiers == modifiers
+    }
+}
+
+impl FromStr for Key {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        // A lone "-" is the minus key rather than a modifier
+        while let Some((modifier, key)) = rest.split_once('-').filter(|(_, key)| !key.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                _ => bail!(
+                    "Unknown modifier {:?} in key {:?}, use ctrl or alt",
+                    modifier,
+                    s
+                ),
+            };
+            rest = key;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => {
+                let name = rest.to_lowercase();
+                let function = name
+                    .strip_prefix('f')
+                    .and_then(|number| number.parse().ok())
+                    .filter(|number| (1..=12).contains(number));
+                match function {
+                    Some(number) => KeyCode::F(number),
+                    None => NAMED_KEYS
+                        .iter()
+                        .find(|(key, _)| *key == name)
+                        .map(|(_, code)| *code)
+                        .ok_or_else(|| eyre!("Unknown key {:?}", s))?,
+                }
+            }
+        };
+
+        Ok(Key { code, modifiers })
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("alt-")?;
+        }
+
+        match NAMED_KEYS.iter().find(|(_, code)| *code == self.code) {
+            Some((name, _)) => f.write_str(name),
+            None => match self.code {
+                KeyCode::Char(c) => write!(f, "{}", c),
+                KeyCode::F(number) => write!(f, "f{}", number),
+                code => write!(f, "{:?}", code),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keys() {
+        let key = |s: &str| s.parse::<Key>().unwrap();
+        assert_eq!(key("q").code, KeyCode::Char('q'));
+        assert_eq!(key("G").code, KeyCode::Char('G'));
+        assert_eq!(key("-").code, KeyCode::Char('-'));
+        assert_eq!(key("Home").code, KeyCode::Home);
+        assert_eq!(key("f5").code, KeyCode::F(5));
+        assert_eq!(key("ctrl-c").modifiers, KeyModifiers::CONTROL);
+        assert_eq!(key("ctrl-alt-x").to_string(), "ctrl-alt-x");
+        assert!("hyper-x".parse::<Key>().is_err());
+        assert!("escape".parse::<Key>().is_err());
+    }
+
+    #[test]
+    fn test_key_map() {
+        let keymap: KeyMap = toml::from_str("quit = \"ctrl-c\"\ndown = [\"n\", \"down\"]").unwrap();
+        let press = |code, modifiers| keymap.action(&KeyEvent::new(code, modifiers));
+
+        assert_eq!(
+            press(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            Some(Action::Quit)
+        );
+        assert_eq!(press(KeyCode::Char('c'), KeyModifiers::NONE), None);
+        assert_eq!(
+            press(KeyCode::Char('n'), KeyModifiers::NONE),
+            Some(Action::Down)
+        );
+        // Overridden keys no longer do anything, others keep their defaults
+        assert_eq!(press(KeyCode::Char('j'), KeyModifiers::NONE), None);
+        assert_eq!(
+            press(KeyCode::Char('k'), KeyModifiers::NONE),
+            Some(Action::Up)
+        );
+        assert_eq!(
+            press(KeyCode::Char('G'), KeyModifiers::SHIFT),
+            Some(Action::Bottom)
+        );
+
+        assert!(toml::from_str::<KeyMap>("up = \"nope\"").is_err());
+    }
+}
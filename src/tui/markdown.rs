@@ -0,0 +1,243 @@
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use ratatui::prelude::*;
+
+/// Style a markdown readme for the info pane: headings bold, list items bulleted, code in
+/// `code_fg` and links underlined. HTML is left out as it can't be shown.
+pub(crate) fn to_text(markdown: &str, code_fg: Color) -> Text<'static> {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut writer = Writer {
+        code_fg,
+        ..Default::default()
+    };
+    for event in Parser::new_ext(markdown, options) {
+        writer.event(event);
+    }
+    writer.finish()
+}
+
+#[derive(Default)]
+struct Writer {
+    code_fg: Color,
+    lines: Vec<Line<'static>>,
+    /// Spans of the line being written
+    spans: Vec<Span<'static>>,
+    /// Styles of the enclosing inline elements, innermost last
+    styles: Vec<Style>,
+    /// Next number of each enclosing list, `None` for bulleted lists
+    lists: Vec<Option<u64>>,
+    quote_depth: usize,
+    in_code_block: bool,
+}
+
+impl Writer {
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) if self.in_code_block => {
+                for line in text.split_inclusive('\n') {
+                    let style = Style::new().fg(self.code_fg);
+                    self.push(Span::styled(line.trim_end_matches('\n').to_string(), style));
+                    if line.ends_with('\n') {
+                        self.end_line();
+                    }
+                }
+            }
+            Event::Text(text) => self.push(Span::styled(text.to_string(), self.style())),
+            Event::Code(code) => {
+                let style = self.style().fg(self.code_fg);
+                self.push(Span::styled(code.to_string(), style));
+            }
+            Event::SoftBreak => self.push(Span::raw(" ")),
+            Event::HardBreak => self.end_line(),
+            Event::Rule => {
+                self.end_line();
+                self.lines.push(Line::from("────────"));
+                self.blank_line();
+            }
+            Event::TaskListMarker(checked) => {
+                self.push(Span::raw(if checked { "[x] " } else { "[ ] " }));
+            }
+            Event::FootnoteReference(name) => self.push(Span::raw(format!("[{}]", name))),
+            Event::InlineMath(math) | Event::DisplayMath(math) => {
+                self.push(Span::styled(math.to_string(), self.style()));
+            }
+            Event::Html(_) | Event::InlineHtml(_) => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Heading { .. } => {
+                self.end_line();
+                self.push_style(Style::new().bold());
+            }
+            Tag::BlockQuote(_) => {
+                self.end_line();
+                self.quote_depth += 1;
+            }
+            Tag::CodeBlock(_) => {
+                self.end_line();
+                self.in_code_block = true;
+            }
+            Tag::List(start) => {
+                self.end_line();
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.end_line();
+                let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}{}. ", indent, *number - 1)
+                    }
+                    _ => format!("{}• ", indent),
+                };
+                self.push(Span::raw(marker));
+            }
+            Tag::Emphasis => self.push_style(Style::new().italic()),
+            Tag::Strong => self.push_style(Style::new().bold()),
+            Tag::Strikethrough => self.push_style(Style::new().crossed_out()),
+            Tag::Link { .. } => self.push_style(Style::new().underlined()),
+            Tag::Image { .. } => {
+                self.push_style(Style::new().dim());
+                self.push(Span::styled("[", self.style()));
+            }
+            Tag::TableCell if !self.spans.is_empty() => self.push(Span::raw(" │ ")),
+            Tag::Paragraph
+            | Tag::HtmlBlock
+            | Tag::FootnoteDefinition(_)
+            | Tag::Table(_)
+            | Tag::TableHead
+            | Tag::TableRow
+            | Tag::TableCell
+            | Tag::MetadataBlock(_) => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph if !self.lists.is_empty() => self.end_line(),
+            TagEnd::Paragraph | TagEnd::CodeBlock | TagEnd::Table => {
+                self.in_code_block = false;
+                self.end_line();
+                self.blank_line();
+            }
+            TagEnd::Heading(_) => {
+                self.styles.pop();
+                self.end_line();
+                self.blank_line();
+            }
+            TagEnd::BlockQuote => {
+                self.end_line();
+                self.quote_depth -= 1;
+                self.blank_line();
+            }
+            TagEnd::List(_) => {
+                self.end_line();
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.blank_line();
+                }
+            }
+            TagEnd::Item | TagEnd::TableHead | TagEnd::TableRow => self.end_line(),
+            TagEnd::Image => {
+                self.push(Span::styled("]", self.style()));
+                self.styles.pop();
+            }
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link => {
+                self.styles.pop();
+            }
+            TagEnd::HtmlBlock
+            | TagEnd::FootnoteDefinition
+            | TagEnd::TableCell
+            | TagEnd::MetadataBlock(_) => {}
+        }
+    }
+
+    fn style(&self) -> Style {
+        self.styles.last().copied().unwrap_or_default()
+    }
+
+    /// Style nested elements with `style` on top of the enclosing ones
+    fn push_style(&mut self, style: Style) {
+        self.styles.push(self.style().patch(style));
+    }
+
+    fn push(&mut self, span: Span<'static>) {
+        if self.spans.is_empty() && self.quote_depth > 0 {
+            self.spans
+                .push(Span::raw("│ ".repeat(self.quote_depth)).dim());
+        }
+        self.spans.push(span);
+    }
+
+    fn end_line(&mut self) {
+        if !self.spans.is_empty() {
+            self.lines.push(Line::from(std::mem::take(&mut self.spans)));
+        }
+    }
+
+    /// Separate blocks by one empty line however many end together
+    fn blank_line(&mut self) {
+        if self.lines.last().is_some_and(|line| line.width() > 0) {
+            self.lines.push(Line::default());
+        }
+    }
+
+    fn finish(mut self) -> Text<'static> {
+        self.end_line();
+        while self.lines.last().is_some_and(|line| line.width() == 0) {
+            self.lines.pop();
+        }
+        Text::from(self.lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(text: &Text) -> Vec<String> {
+        text.lines.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_markdown_to_text() {
+        let text = to_text(
+            "# Porgi\n\nA `corgi` [for projects](https://example.com).\n\n\
+             - one\n- two\n  1. nested\n\n> quoted\n\n```\nlet a = 1;\n```\n<p>html</p>\n",
+            Color::Cyan,
+        );
+
+        assert_eq!(
+            plain(&text),
+            [
+                "Porgi",
+                "",
+                "A corgi for projects.",
+                "",
+                "• one",
+                "• two",
+                "  1. nested",
+                "",
+                "│ quoted",
+                "",
+                "let a = 1;",
+            ]
+        );
+
+        let heading = &text.lines[0].spans[0];
+        assert!(heading.style.add_modifier.contains(Modifier::BOLD));
+        let paragraph = &text.lines[2].spans;
+        assert_eq!(paragraph[1].content, "corgi");
+        assert_eq!(paragraph[1].style.fg, Some(Color::Cyan));
+        assert_eq!(paragraph[3].content, "for projects");
+        assert!(paragraph[3]
+            .style
+            .add_modifier
+            .contains(Modifier::UNDERLINED));
+    }
+}
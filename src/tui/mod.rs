@@ -1,3 +1,4 @@
+use arboard::Clipboard;
 use color_eyre::config::HookBuilder;
 use crossterm::{
     event::{Event, EventStream, KeyCode, KeyEventKind},
@@ -5,33 +6,202 @@ use crossterm::{
     ExecutableCommand,
 };
 use eyre::Result;
-use futures::{future::FutureExt, select, StreamExt};
+use futures::{
+    future::{self, FutureExt},
+    select, StreamExt,
+};
 use ratatui::{prelude::*, style::palette::tailwind, widgets::*};
 
-const INFO_TEXT: &str =
-    "(Esc) quit | (↑) move up | (↓) move down | (o) open project | (←) unselect";
+const INFO_TEXT: &str = "(↑/↓) move | (o) open | (/) search | (f) filter | (?) help";
+const SEARCH_INFO_TEXT: &str =
+    "(Enter) done | (Esc) clear search | start with (/) to also search READMEs";
+const CONTENT_SEARCH_INFO_TEXT: &str =
+    "Searching names and READMEs | (Enter) done | (Esc) clear search";
+const CONFIRM_QUIT_TEXT: &str = "Quit porgi? (y) yes | any other key to stay";
+const TEMPLATE_INFO_TEXT: &str = "(Enter) create | (Esc) cancel";
+const TAG_INFO_TEXT: &str = "(Enter) save | (Esc) cancel";
+const DIFF_LOADING_TEXT: &str = "Reading changes…";
+const FILTER_INFO_TEXT: &str =
+    "(↑/↓) choose | (Space/←/→) change | (Enter) apply | (Esc) cancel | (Del) clear all";
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{
-    io::{self, stdout},
+    collections::HashMap,
+    io::{self, stdout, Write},
+    path::{Path, PathBuf},
+    process::{ExitStatus, Stdio},
     sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    process,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    time::Instant,
 };
 
 use crate::{
-    config::Config,
-    project::{Project, ProjectLoader},
-    tui::project_table::ProjectTable,
+    config::{clamp_split_ratio, Config},
+    project::{
+        copy_template, diff_stat, open_url, parse_tags, template_destination, Pins, Project,
+        ProjectEvent, ProjectKey, ProjectKind, ProjectLoader, Tags,
+    },
+    tui::{
+        image_preview::ImagePreview, info_section::InfoSection, keymap::Action,
+        project_table::ProjectTable, readme_cache::ReadmeCache,
+    },
 };
 
+mod filter_popup;
+mod help;
+mod image_preview;
+mod info_section;
+mod keymap;
+mod markdown;
 mod project_table;
+mod readme_cache;
+
+pub use keymap::KeyMap;
+pub use project_table::{ColumnConfig, LeftAction, NameTruncation};
+
+/// Delay before running `on_select_command` so scrolling through the list
+/// doesn't spawn a process for every row passed over
+const ON_SELECT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Percentage the split moves by for each `<` or `>`
+const SPLIT_STEP: u16 = 5;
+
+/// Narrowest terminal the marked project is previewed beside the selected one in
+const COMPARE_MIN_WIDTH: u16 = 150;
 
-#[derive(Debug, Deserialize)]
+/// How the screen is split between the project list and the info pane
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaneLayout {
+    /// Project list beside the info pane
+    #[default]
+    Split,
+    /// Full width project list with extra columns and no info pane
+    Table,
+}
+
+impl PaneLayout {
+    fn toggle(self) -> Self {
+        match self {
+            PaneLayout::Split => PaneLayout::Table,
+            PaneLayout::Table => PaneLayout::Split,
+        }
+    }
+}
+
+/// Keys that quit porgi
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum QuitKey {
+    #[default]
+    #[serde(rename = "esc")]
+    Esc,
+    #[serde(rename = "q")]
+    Q,
+    /// Vim's `ZZ`
+    #[serde(rename = "ZZ")]
+    ZZ,
+    /// Vim's `:q`
+    #[serde(rename = ":q")]
+    ColonQ,
+}
+
+impl QuitKey {
+    /// Keys to press in order
+    fn keys(self) -> &'static [KeyCode] {
+        match self {
+            QuitKey::Esc => &[KeyCode::Esc],
+            QuitKey::Q => &[KeyCode::Char('q')],
+            QuitKey::ZZ => &[KeyCode::Char('Z'), KeyCode::Char('Z')],
+            QuitKey::ColonQ => &[KeyCode::Char(':'), KeyCode::Char('q')],
+        }
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            QuitKey::Esc => "Esc",
+            QuitKey::Q => "q",
+            QuitKey::ZZ => "ZZ",
+            QuitKey::ColonQ => ":q",
+        }
+    }
+
+    /// Advance through the quit keys given how many have been pressed so far, returning
+    /// how many now have. A full sequence returns the sequence's length.
+    fn advance(self, pressed: usize, code: KeyCode) -> usize {
+        let keys = self.keys();
+        if keys.get(pressed) == Some(&code) {
+            pressed + 1
+        } else if keys[0] == code {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+/// Actions asked for with a `y` before they are carried out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Confirm {
+    Quit,
+    /// Open every listed project, there being this many
+    OpenShown(usize),
+}
+
+impl Confirm {
+    fn prompt(self) -> String {
+        match self {
+            Confirm::Quit => CONFIRM_QUIT_TEXT.to_string(),
+            Confirm::OpenShown(count) => format!(
+                "Open {} projects one after another? (y) yes | any other key to cancel",
+                count
+            ),
+        }
+    }
+}
+
+/// New project being named, to be created by copying a template project
+struct TemplatePrompt {
+    template: PathBuf,
+    template_name: String,
+    /// Name or path typed so far, relative paths are beside the template
+    input: String,
+}
+
+/// Tags being edited for a project, saved when Enter is pressed
+struct TagPrompt {
+    project: ProjectKey,
+    name: String,
+    /// Tags separated by spaces or commas, starting with the project's current tags
+    input: String,
+}
+
+/// Colors used throughout the UI, any left out of the config keep their default
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct ColorConfig {
     normal_row_color: Color,
     selected_style_fg: Color,
     text_color: Color,
     project_header_bg: Color,
     footer_border_color: Color,
+    /// Column names above the project list
+    column_header_fg: Color,
+    /// Backgrounds alternated between project rows when `striped_rows` is set
+    even_row_color: Color,
+    odd_row_color: Color,
+    /// Background of projects a rescan has just found
+    new_project_bg: Color,
+    /// Marker after the names of projects with uncommitted changes
+    dirty_marker_fg: Color,
+    /// Code in markdown readmes
+    markdown_code_fg: Color,
+    /// Project name colors by kind, e.g. `Rust = "#dea584"`. Names of kinds left out keep
+    /// the text color.
+    kinds: HashMap<String, Color>,
 }
 
 impl Default for ColorConfig {
@@ -42,10 +212,24 @@ impl Default for ColorConfig {
             text_color: tailwind::SLATE.c200,
             project_header_bg: tailwind::BLUE.c950,
             footer_border_color: tailwind::BLUE.c300,
+            column_header_fg: tailwind::BLUE.c300,
+            even_row_color: tailwind::SLATE.c950,
+            odd_row_color: tailwind::SLATE.c900,
+            new_project_bg: tailwind::GREEN.c900,
+            dirty_marker_fg: tailwind::AMBER.c400,
+            markdown_code_fg: tailwind::EMERALD.c300,
+            kinds: HashMap::new(),
         }
     }
 }
 
+impl ColorConfig {
+    /// Color of the names of projects of `kind`, if one is configured
+    fn kind_color(&self, kind: &ProjectKind) -> Option<Color> {
+        self.kinds.get(&kind.to_string()).copied()
+    }
+}
+
 /// This struct holds the current state of the app. In particular, it has the `items` field which is
 /// a wrapper around `ListState`. Keeping track of the items state let us render the associated
 /// widget with its state and have access to features such as natural scrolling.
@@ -57,6 +241,70 @@ pub(crate) struct App {
     config: Arc<Config>,
     items: ProjectTable,
     project_events: ProjectLoader,
+    /// Projects found by the scan in progress, `None` once every project has been walked
+    scanning: Option<usize>,
+    layout: PaneLayout,
+    /// Percentage of the width given to the project list in the split layout
+    split_ratio: u16,
+    /// Transient message shown in the footer until the next key press
+    status: Option<String>,
+    /// Number of the quit sequence's keys pressed so far
+    quit_keys_pressed: usize,
+    /// Action waiting for the user to confirm it
+    confirming: Option<Confirm>,
+    /// Name for a copy of a template project, being typed in the footer
+    template_prompt: Option<TemplatePrompt>,
+    tag_prompt: Option<TagPrompt>,
+    /// Show every key in a popup over the body
+    show_help: bool,
+    clipboard: Option<Clipboard>,
+    image_preview: Option<ImagePreview>,
+    /// Readmes read on demand when `lazy_readme` is set
+    readme_cache: Option<ReadmeCache>,
+    /// Show uncommitted changes instead of the readme for dirty projects
+    show_diff: bool,
+    /// Diff stat of the last project it was shown for, `None` while it is worked out
+    diff_stat: Option<(ProjectKey, Option<String>)>,
+    info_sections: Vec<InfoSection>,
+    /// Project marked to preview beside the selected one on wide terminals
+    compare_key: Option<ProjectKey>,
+    /// Selected project `on_select_command` has been or will be run for
+    on_select_key: Option<ProjectKey>,
+    /// When to run `on_select_command` for `on_select_key`
+    on_select_deadline: Option<Instant>,
+    /// Directory to report to the terminal once porgi exits
+    exit_dir: Option<PathBuf>,
+    background_tx: UnboundedSender<Background>,
+    background_rx: UnboundedReceiver<Background>,
+}
+
+/// Result of work done off the UI task, so slow disks and large files don't hold up
+/// drawing and input
+pub(crate) enum Background {
+    /// Decoded logo of the project, `None` if it couldn't be read
+    Image(ProjectKey, Option<image::DynamicImage>),
+    /// Diff stat of the project, or why it couldn't be worked out
+    DiffStat(ProjectKey, String),
+}
+
+fn is_markdown(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+}
+
+/// Where the output of `startup_command` is written, replaced each time porgi starts
+fn startup_log_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("porgi").join("startup.log"))
+}
+
+fn open_startup_log() -> io::Result<std::fs::File> {
+    let path = startup_log_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no cache directory"))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::File::create(path)
 }
 
 pub(crate) fn init_error_hooks() -> color_eyre::Result<()> {
@@ -88,51 +336,630 @@ pub(crate) fn restore_terminal() -> color_eyre::Result<()> {
     Ok(())
 }
 
+/// Tell the terminal its working directory is `path` so new tabs and panes can open there.
+///
+/// Emits both OSC 7 and OSC 9;9, the sequence Windows Terminal and ConEmu use. Terminals
+/// that don't support them ignore the sequences, and a shell reporting its own directory
+/// at the next prompt replaces this report.
+pub(crate) fn report_working_directory(path: &Path) -> io::Result<()> {
+    let host = gethostname::gethostname();
+    let mut out = stdout();
+    write!(
+        out,
+        "\x1b]7;file://{}{}\x07",
+        host.to_string_lossy(),
+        percent_encode_path(path)
+    )?;
+    write!(out, "\x1b]9;9;{}\x07", path.display())?;
+    out.flush()
+}
+
+fn percent_encode_path(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    #[cfg(windows)]
+    let path = path.replace('\\', "/");
+    let mut encoded = String::new();
+
+    if !path.starts_with('/') {
+        // Windows paths such as C:/src need a leading slash to be a valid URL path
+        encoded.push('/');
+    }
+
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'.' | b'_' | b'~' | b':' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
+
 impl App {
     pub(crate) fn new(config: Arc<Config>, project_events: ProjectLoader) -> Self {
+        let (background_tx, background_rx) = unbounded_channel();
+        let image_preview = if config.image_preview {
+            ImagePreview::detect()
+        } else {
+            None
+        };
+
+        let (info_sections, unknown) = InfoSection::parse(&config.info_sections);
+        let status = (!unknown.is_empty())
+            .then(|| format!("Skipping unknown info_sections: {}", unknown.join(", ")));
+
         Self {
             quit: false,
+            items: ProjectTable::new(&config),
+            layout: config.layout,
+            split_ratio: config.split_ratio(),
+            readme_cache: config.lazy_readme.then(ReadmeCache::default),
             config,
-            items: ProjectTable::new(),
             project_events,
+            scanning: Some(0),
+            status,
+            quit_keys_pressed: 0,
+            confirming: None,
+            show_help: false,
+            template_prompt: None,
+            tag_prompt: None,
+            clipboard: None,
+            image_preview,
+            show_diff: false,
+            diff_stat: None,
+            info_sections,
+            compare_key: None,
+            on_select_key: None,
+            on_select_deadline: None,
+            exit_dir: None,
+            background_tx,
+            background_rx,
+        }
+    }
+
+    /// List the projects in `pins` first, pinning and unpinning with (p) saves them
+    pub(crate) fn with_pins(self, pins: Pins) -> Self {
+        Self {
+            items: self.items.with_pins(pins),
+            ..self
+        }
+    }
+
+    /// Give projects the tags in `tags`, editing them with (#) saves them
+    pub(crate) fn with_tags(self, tags: Tags) -> Self {
+        Self {
+            items: self.items.with_tags(tags),
+            ..self
+        }
+    }
+
+    pub(crate) fn split_ratio(&self) -> u16 {
+        self.split_ratio
+    }
+
+    /// Project directory chosen to exit into, if any
+    pub(crate) fn exit_dir(&self) -> Option<&Path> {
+        self.exit_dir.as_deref()
+    }
+
+    /// Rescan projects unless the user is in the middle of something, as dropping deleted
+    /// projects could move or remove the one a prompt or dialog is about
+    fn refresh(&mut self) {
+        if self.items.is_searching()
+            || self.items.is_filtering()
+            || self.template_prompt.is_some()
+            || self.tag_prompt.is_some()
+            || self.confirming.is_some()
+            || self.show_help
+        {
+            return;
+        }
+
+        self.items.begin_rescan();
+        if let Some(readme_cache) = &mut self.readme_cache {
+            readme_cache.clear();
+        }
+        self.diff_stat = None;
+        self.scanning = Some(0);
+        self.project_events.refresh();
+    }
+
+    /// Take in the result of work done off the UI task
+    fn finish_background(&mut self, done: Background) {
+        match done {
+            Background::Image(key, image) => {
+                if let Some(image_preview) = &mut self.image_preview {
+                    image_preview.loaded(key, image);
+                }
+            }
+            Background::DiffStat(key, stat) => {
+                // Dropped if another project has been selected since
+                if let Some((current, pending @ None)) = &mut self.diff_stat {
+                    if *current == key {
+                        *pending = Some(stat);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Work out the diff stat of `project` off the UI task, large repositories take a while
+    fn spawn_diff_stat(&self, project: &Project) {
+        let key = project.key().clone();
+        let path = project.path.clone();
+        let background = self.background_tx.clone();
+        tokio::task::spawn_blocking(move || {
+            let stat =
+                diff_stat(&path).unwrap_or_else(|e| format!("Could not diff: {}", e.message()));
+            // Fails only once the app is gone
+            let _ = background.send(Background::DiffStat(key, stat));
+        });
+    }
+
+    /// (Re)start the debounce timer for `on_select_command` if the selection changed
+    fn schedule_on_select(&mut self) {
+        if self.config.on_select_command.is_none() {
+            return;
+        }
+
+        let selected = self.items.current().map(|project| project.key().clone());
+
+        if selected != self.on_select_key {
+            self.on_select_deadline = selected
+                .as_ref()
+                .map(|_| Instant::now() + ON_SELECT_DEBOUNCE);
+            self.on_select_key = selected;
+        }
+    }
+
+    /// Spawn `on_select_command` for the selected project without waiting for it
+    fn run_on_select_command(&mut self) {
+        let (Some(command), Some(path)) = (&self.config.on_select_command, &self.on_select_key)
+        else {
+            return;
+        };
+
+        let Some((program, args)) = command.split_first() else {
+            return;
+        };
+
+        let spawned = process::Command::new(program)
+            .args(args)
+            .arg(path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+
+        if let Err(e) = spawned {
+            self.status = Some(format!("Failed to run on_select_command: {}", e));
+        }
+    }
+
+    /// Spawn `startup_command` without waiting for it, its output going to the startup log
+    fn spawn_startup_command(&mut self) -> Option<process::Child> {
+        let (program, args) = self.config.startup_command.as_ref()?.split_first()?;
+
+        let (stdout, stderr) = match open_startup_log().and_then(|log| Ok((log.try_clone()?, log)))
+        {
+            Ok((out, err)) => (Stdio::from(out), Stdio::from(err)),
+            Err(e) => {
+                self.status = Some(format!("Could not open startup log: {}", e));
+                (Stdio::null(), Stdio::null())
+            }
+        };
+
+        let spawned = process::Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(stdout)
+            .stderr(stderr)
+            .spawn();
+
+        match spawned {
+            Ok(child) => Some(child),
+            Err(e) => {
+                self.status = Some(format!("Failed to run startup_command: {}", e));
+                None
+            }
+        }
+    }
+
+    fn report_startup_exit(&mut self, status: io::Result<ExitStatus>) {
+        let log = startup_log_path()
+            .map(|path| format!(", see {}", self.config.display_path(&path)))
+            .unwrap_or_default();
+
+        match status {
+            Ok(status) if status.success() => {}
+            Ok(status) => {
+                self.status = Some(format!("startup_command failed ({}){}", status, log));
+            }
+            Err(e) => self.status = Some(format!("startup_command failed: {}{}", e, log)),
+        }
+    }
+
+    fn copy_to_clipboard(&mut self, text: String) -> Result<(), arboard::Error> {
+        let clipboard = match &mut self.clipboard {
+            Some(clipboard) => clipboard,
+            None => self.clipboard.insert(Clipboard::new()?),
+        };
+
+        clipboard.set_text(text)
+    }
+
+    fn copy_project_info(&mut self) {
+        let Some(project) = self.items.current() else {
+            return;
+        };
+
+        let name = project.name.clone();
+        let markdown = project.to_markdown();
+
+        self.status = Some(match self.copy_to_clipboard(markdown) {
+            Ok(()) => format!("Copied info for {} as markdown", name),
+            Err(e) => format!("Could not copy to clipboard: {}", e),
+        });
+    }
+
+    /// Copy the selected project's absolute path, to paste into another terminal
+    fn copy_path(&mut self) {
+        let Some(project) = self.items.current() else {
+            return;
+        };
+
+        let path = std::path::absolute(&project.path).unwrap_or_else(|_| project.path.clone());
+        self.status = Some(match self.copy_to_clipboard(path.display().to_string()) {
+            Ok(()) => format!("Copied {}", self.config.display_path(&path)),
+            Err(e) => format!("Could not copy to clipboard: {}", e),
+        });
+    }
+
+    /// Copy the paths of every listed project, one per line, for use in scripts
+    fn copy_shown_paths(&mut self) {
+        let paths: Vec<String> = self
+            .items
+            .shown()
+            .map(|project| project.path.display().to_string())
+            .collect();
+
+        if paths.is_empty() {
+            self.status = Some("No projects to copy".to_string());
+            return;
+        }
+
+        let count = paths.len();
+        self.status = Some(match self.copy_to_clipboard(paths.join("\n")) {
+            Ok(()) => format!("Copied {} project paths", count),
+            Err(e) => format!("Could not copy to clipboard: {}", e),
+        });
+    }
+
+    fn open_web_editor(&mut self) {
+        let Some(project) = self.items.current() else {
+            return;
+        };
+
+        let url = project.git.as_ref().and_then(|git| git.web_editor_url());
+
+        self.status = match url {
+            Some(url) => open_url(&url).err().map(|e| e.to_string()),
+            None => Some(format!(
+                "{} has no remote that can be opened in a web editor",
+                project.name
+            )),
+        };
+    }
+
+    fn open_remote(&mut self) {
+        let Some(project) = self.items.current() else {
+            return;
+        };
+
+        let url = project.git.as_ref().and_then(|git| git.web_url());
+
+        self.status = match url {
+            Some(url) => open_url(&url).err().map(|e| e.to_string()),
+            None => Some(format!(
+                "{} has no remote that can be opened in a browser",
+                project.name
+            )),
+        };
+    }
+
+    /// Mark the selected project for previewing beside other projects, or clear the mark
+    /// if it is already marked
+    fn toggle_compare_mark(&mut self) {
+        let Some(project) = self.items.current() else {
+            return;
+        };
+
+        if self.compare_key.as_ref() == Some(project.key()) {
+            self.compare_key = None;
+            self.status = Some(format!("Stopped comparing with {}", project.name));
+        } else {
+            self.compare_key = Some(project.key().clone());
+            self.status = Some(if self.layout == PaneLayout::Split {
+                format!("Comparing other projects with {}", project.name)
+            } else {
+                format!(
+                    "Comparing other projects with {} in the split layout",
+                    project.name
+                )
+            });
+        }
+    }
+
+    /// Launch `terminal_command` in the selected project without waiting for it
+    fn open_terminal(&mut self) {
+        let Some(project) = self.items.current() else {
+            return;
+        };
+
+        let Some((program, args)) = self
+            .config
+            .terminal_command
+            .as_ref()
+            .and_then(|command| command.split_first())
+        else {
+            self.status = Some("Set terminal_command to open projects in a terminal".to_string());
+            return;
+        };
+
+        let spawned = process::Command::new(program)
+            .args(args.iter().map(|arg| project.expand_placeholders(arg)))
+            .current_dir(&project.path)
+            .envs(&project.config.env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+
+        if let Err(e) = spawned {
+            self.status = Some(format!("Failed to run terminal_command: {}", e));
+        }
+    }
+
+    fn open_issues(&mut self) {
+        let Some(project) = self.items.current() else {
+            return;
+        };
+
+        let url = project
+            .git
+            .as_ref()
+            .and_then(|git| git.issues_url(&self.config.issue_trackers));
+
+        self.status = match url {
+            Some(url) => open_url(&url).err().map(|e| e.to_string()),
+            None => Some(format!(
+                "{} has no remote with a known issue tracker",
+                project.name
+            )),
+        };
+    }
+
+    /// Start naming a copy of the selected project
+    fn prompt_for_template_copy(&mut self) {
+        if let Some(project) = self.items.current() {
+            self.template_prompt = Some(TemplatePrompt {
+                template: project.path.clone(),
+                template_name: project.name.clone(),
+                input: String::new(),
+            });
+        }
+    }
+
+    /// Copy the template to where the user asked and run `template_command` in the copy
+    async fn create_from_template(&mut self, prompt: TemplatePrompt) {
+        if prompt.input.trim().is_empty() {
+            return;
+        }
+
+        let dest = template_destination(&prompt.template, &prompt.input);
+        let walk = self.config.walk.clone();
+        let copy = {
+            let dest = dest.clone();
+            tokio::task::spawn_blocking(move || copy_template(&prompt.template, &dest, &walk))
+        };
+        match copy.await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                self.status = Some(format!("{:#}", e));
+                return;
+            }
+            Err(e) => {
+                self.status = Some(format!("Could not copy the template: {}", e));
+                return;
+            }
+        }
+
+        let dest_name = self.config.display_path(&dest);
+        self.status = Some(format!("Created {}", dest_name));
+        if let Some((program, args)) = self
+            .config
+            .template_command
+            .as_ref()
+            .and_then(|command| command.split_first())
+        {
+            let output = process::Command::new(program)
+                .args(args)
+                .current_dir(&dest)
+                .stdin(Stdio::null())
+                .output()
+                .await;
+
+            match output {
+                Ok(output) if output.status.success() => {}
+                Ok(output) => {
+                    self.status = Some(format!(
+                        "Created {}, template_command failed ({})",
+                        dest_name, output.status
+                    ));
+                }
+                Err(e) => {
+                    self.status = Some(format!(
+                        "Created {}, failed to run template_command: {}",
+                        dest_name, e
+                    ));
+                }
+            }
         }
+
+        // Pick up the new project if it is in one of the project directories
+        self.refresh();
     }
 
     async fn open_project(&mut self) -> Result<()> {
         if let Some(project) = self.items.current() {
-            self.config.opener.open(project).await?;
+            let retry = self.config.open_retry();
+            self.config.opener(project).open(project, &retry).await?;
         }
 
         Ok(())
     }
+
+    /// Open every listed project one after another, stopping at the first that fails
+    async fn open_shown_projects(&mut self) {
+        let retry = self.config.open_retry();
+        let mut opened = 0;
+        for project in self.items.shown() {
+            if let Err(e) = self.config.opener(project).open(project, &retry).await {
+                self.status = Some(format!(
+                    "Opened {} projects, could not open {}: {}",
+                    opened, project.name, e
+                ));
+                return;
+            }
+            opened += 1;
+        }
+
+        self.status = Some(format!("Opened {} projects", opened));
+    }
 }
 
 impl App {
     pub(crate) async fn run(&mut self, mut terminal: Terminal<impl Backend>) -> Result<()> {
         let mut reader = EventStream::new();
 
+        let mut refresh_interval = self.config.auto_refresh_interval.map(|minutes| {
+            let period = Duration::from_secs(minutes.max(1) * 60);
+            let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            interval
+        });
+
+        let mut startup_exit = match self.spawn_startup_command() {
+            Some(mut child) => async move { child.wait().await }.boxed(),
+            None => future::pending().boxed(),
+        }
+        .fuse();
+
+        let idle_timeout = self.config.idle_timeout.map(Duration::from_secs);
+        let mut idle_deadline = idle_timeout.map(|timeout| Instant::now() + timeout);
+
         while !self.quit {
             self.draw(&mut terminal)?;
 
             let mut event = reader.next().fuse();
-            let mut project_event_fut = self.project_events.next().fuse();
+            // Once scanning finishes there is nothing to wait for until the next refresh
+            let mut project_event_fut = if self.project_events.is_finished() {
+                future::Either::Left(future::pending())
+            } else {
+                future::Either::Right(self.project_events.next())
+            }
+            .fuse();
+            let mut on_select_timer = match self.on_select_deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).boxed(),
+                None => future::pending().boxed(),
+            }
+            .fuse();
+            let mut key_sequence_timer = match self.items.pending_key_deadline() {
+                Some(deadline) => tokio::time::sleep_until(deadline).boxed(),
+                None => future::pending().boxed(),
+            }
+            .fuse();
+            let mut new_project_timer = match self.items.new_project_deadline() {
+                Some(deadline) => tokio::time::sleep_until(deadline).boxed(),
+                None => future::pending().boxed(),
+            }
+            .fuse();
+            let mut idle_timer = match idle_deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).boxed(),
+                None => future::pending().boxed(),
+            }
+            .fuse();
+            let mut background = future::poll_fn(|cx| self.background_rx.poll_recv(cx)).fuse();
+            let mut refresh_tick = match &mut refresh_interval {
+                Some(interval) => interval.tick().map(|_| ()).boxed(),
+                None => future::pending().boxed(),
+            }
+            .fuse();
 
             select! {
                 project_event = project_event_fut => {
-                    if let Some(project_event) = project_event.transpose()? {
-                        self.items.update(project_event)?;
+                    match project_event {
+                        Some(Ok(project_event)) => {
+                            match (&project_event, &mut self.scanning) {
+                                (ProjectEvent::Add(_), Some(found)) => *found += 1,
+                                (ProjectEvent::Scanned, _) => self.scanning = None,
+                                (ProjectEvent::WatchFailed(e), _) => {
+                                    self.status = Some(format!("Watching failed: {}", e));
+                                }
+                                _ => {}
+                            }
+                            self.items.update(project_event)?;
+                            self.schedule_on_select();
+                        }
+                        // Projects found before the error stay listed
+                        Some(Err(e)) => {
+                            self.scanning = None;
+                            self.status = Some(format!("Scan failed: {:#}", e));
+                        }
+                        None => self.scanning = None,
                     }
                 },
                 maybe_event = event => {
                     match maybe_event {
                         Some(Ok(event)) => {
                             self.handle_input(&mut terminal, event).await?;
+                            // After handling so time spent in an opened editor isn't idle
+                            idle_deadline = idle_timeout.map(|timeout| Instant::now() + timeout);
+                            self.schedule_on_select();
                         }
                         Some(Err(e)) => {
                             eprintln!("Error: {}", e);
                         }
                         None => break,
                     }
+                },
+                _ = on_select_timer => {
+                    self.on_select_deadline = None;
+                    self.run_on_select_command();
+                },
+                _ = key_sequence_timer => {
+                    self.items.flush_pending_key();
+                    self.schedule_on_select();
+                },
+                _ = idle_timer => {
+                    self.quit = true;
+                },
+                _ = new_project_timer => {
+                    self.items.fade_new_projects();
+                },
+                _ = refresh_tick => {
+                    self.refresh();
+                },
+                // The app holds a sender so this never ends
+                done = background => {
+                    if let Some(done) = done {
+                        self.finish_background(done);
+                    }
+                },
+                status = startup_exit => {
+                    self.report_startup_exit(status);
                 }
             };
         }
@@ -147,19 +974,209 @@ impl App {
     ) -> Result<()> {
         use KeyCode::*;
 
+        if self.items.is_searching() || self.items.is_filtering() {
+            return self.items.handle_input(event).await;
+        }
+
         match event {
             Event::Key(key) if key.kind == KeyEventKind::Press => {
+                self.status = None;
+
+                if self.show_help {
+                    if matches!(key.code, KeyCode::Char('?') | Esc) {
+                        self.show_help = false;
+                    }
+                    return Ok(());
+                }
+
+                if let Some(mut prompt) = self.template_prompt.take() {
+                    match key.code {
+                        Enter => self.create_from_template(prompt).await,
+                        Esc => {}
+                        Backspace => {
+                            prompt.input.pop();
+                            self.template_prompt = Some(prompt);
+                        }
+                        KeyCode::Char(c) => {
+                            prompt.input.push(c);
+                            self.template_prompt = Some(prompt);
+                        }
+                        _ => self.template_prompt = Some(prompt),
+                    }
+                    return Ok(());
+                }
+
+                if let Some(mut prompt) = self.tag_prompt.take() {
+                    match key.code {
+                        Enter => {
+                            let tags = parse_tags(&prompt.input);
+                            if let Err(e) = self.items.set_tags(&prompt.project, tags) {
+                                self.status = Some(format!("{:#}", e));
+                            }
+                        }
+                        Esc => {}
+                        Backspace => {
+                            prompt.input.pop();
+                            self.tag_prompt = Some(prompt);
+                        }
+                        KeyCode::Char(c) => {
+                            prompt.input.push(c);
+                            self.tag_prompt = Some(prompt);
+                        }
+                        _ => self.tag_prompt = Some(prompt),
+                    }
+                    return Ok(());
+                }
+
+                if let Some(confirm) = self.confirming.take() {
+                    if key.code == KeyCode::Char('y') {
+                        match confirm {
+                            Confirm::Quit => self.quit = true,
+                            Confirm::OpenShown(_) => {
+                                self.open_shown_projects().await;
+                                terminal.clear()?;
+                                self.draw(terminal)?;
+                            }
+                        }
+                    }
+                    return Ok(());
+                }
+
+                let action = self.config.keybindings.action(&key);
+                let quits = if self.config.keybindings.quit.is_some() {
+                    action == Some(Action::Quit)
+                } else {
+                    let quit_key = self.config.quit_key;
+                    self.quit_keys_pressed = quit_key.advance(self.quit_keys_pressed, key.code);
+                    if self.quit_keys_pressed > 0 && self.quit_keys_pressed < quit_key.keys().len()
+                    {
+                        return Ok(());
+                    }
+                    self.quit_keys_pressed == quit_key.keys().len()
+                };
+                if quits {
+                    self.quit_keys_pressed = 0;
+                    if self.config.confirm_quit {
+                        self.confirming = Some(Confirm::Quit);
+                    } else {
+                        self.quit = true;
+                    }
+                    return Ok(());
+                }
+
+                if action == Some(Action::Open) {
+                    // So far it seem sufficient to clear and force a redraw
+                    // but we may want to restore the terminal first before
+                    // launching an editor that runs in the terminal.
+                    self.open_project().await?;
+                    terminal.clear()?;
+                    self.draw(terminal)?;
+                    return Ok(());
+                }
+
                 match key.code {
+                    // Keys bound to moving go to the project table whatever they'd do here
+                    _ if action.is_some() => {}
                     Esc => {
-                        self.quit = true;
+                        self.items.clear_filter();
+                        return Ok(());
+                    }
+                    KeyCode::Char('O') => {
+                        match self.items.shown().count() {
+                            0 => self.status = Some("No projects to open".to_string()),
+                            count => self.confirming = Some(Confirm::OpenShown(count)),
+                        }
+                        return Ok(());
+                    }
+                    KeyCode::Char('c') => {
+                        self.copy_project_info();
+                        return Ok(());
+                    }
+                    KeyCode::Char('C') => {
+                        self.copy_shown_paths();
+                        return Ok(());
+                    }
+                    KeyCode::Char('y') => {
+                        self.copy_path();
+                        return Ok(());
+                    }
+                    KeyCode::Char('w') => {
+                        self.open_web_editor();
+                        return Ok(());
+                    }
+                    KeyCode::Char('b') => {
+                        self.open_remote();
+                        return Ok(());
+                    }
+                    KeyCode::Char('i') => {
+                        self.open_issues();
+                        return Ok(());
+                    }
+                    KeyCode::Char('t') => {
+                        self.open_terminal();
+                        return Ok(());
+                    }
+                    KeyCode::Char('m') => {
+                        self.toggle_compare_mark();
+                        return Ok(());
                     }
-                    KeyCode::Char('o') => {
-                        // So far it seem sufficient to clear and force a redraw
-                        // but we may want to restore the terminal first before
-                        // launching an editor that runs in the terminal.
-                        self.open_project().await?;
-                        terminal.clear()?;
-                        self.draw(terminal)?;
+                    KeyCode::Char('N') => {
+                        self.prompt_for_template_copy();
+                        return Ok(());
+                    }
+                    KeyCode::Char('?') => {
+                        self.show_help = true;
+                        return Ok(());
+                    }
+                    Enter if self.config.cd_on_exit => {
+                        if let Some(project) = self.items.current() {
+                            self.exit_dir = Some(project.path.clone());
+                            self.quit = true;
+                        }
+                        return Ok(());
+                    }
+                    Tab => {
+                        self.layout = self.layout.toggle();
+                        return Ok(());
+                    }
+                    KeyCode::Char('<') => {
+                        self.split_ratio =
+                            clamp_split_ratio(self.split_ratio.saturating_sub(SPLIT_STEP));
+                        return Ok(());
+                    }
+                    KeyCode::Char('>') => {
+                        self.split_ratio = clamp_split_ratio(self.split_ratio + SPLIT_STEP);
+                        return Ok(());
+                    }
+                    KeyCode::Char('D') => {
+                        self.show_diff = !self.show_diff;
+                        // Pick up changes made since the diff was last shown
+                        self.diff_stat = None;
+                        return Ok(());
+                    }
+                    KeyCode::Char('#') => {
+                        if let Some(project) = self.items.current() {
+                            self.tag_prompt = Some(TagPrompt {
+                                project: project.key().clone(),
+                                name: project.name.clone(),
+                                input: project.tags.join(" "),
+                            });
+                        }
+                        return Ok(());
+                    }
+                    KeyCode::Char('p') => {
+                        self.status = match self.items.toggle_pin_current() {
+                            Ok(Some((name, true))) => Some(format!("Pinned {}", name)),
+                            Ok(Some((name, false))) => Some(format!("Unpinned {}", name)),
+                            Ok(None) => None,
+                            Err(e) => Some(format!("{:#}", e)),
+                        };
+                        return Ok(());
+                    }
+                    KeyCode::Char('u') => {
+                        if let Err(e) = self.items.touch_current() {
+                            self.status = Some(e.to_string());
+                        }
                         return Ok(());
                     }
                     _ => {}
@@ -173,48 +1190,159 @@ impl App {
     }
 
     fn draw(&mut self, terminal: &mut Terminal<impl Backend>) -> io::Result<()> {
-        terminal.draw(|f| f.render_widget(self, f.size()))?;
+        terminal.draw(|f| f.render_widget(self, f.area()))?;
         Ok(())
     }
 }
 
 impl Widget for &mut App {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        let (min_width, min_height) = self.config.min_terminal_size();
+        if area.width < min_width || area.height < min_height {
+            self.render_too_small(min_width, min_height, area, buf);
+            return;
+        }
+
         let rects = Layout::vertical([Constraint::Min(5), Constraint::Length(3)]).split(area);
 
         self.render_body(rects[0], buf);
         self.render_footer(rects[1], buf);
+        self.items.render_filter_popup(&self.config, rects[0], buf);
+        if self.show_help {
+            help::render(&self.config, rects[0], buf);
+        }
     }
 }
 
 impl App {
+    /// Shown instead of the layout while the terminal is smaller than `min_width` x `min_height`
+    fn render_too_small(&self, min_width: u16, min_height: u16, area: Rect, buf: &mut Buffer) {
+        let text = format!(
+            "Terminal too small ({}x{}), resize to at least {}x{}",
+            area.width, area.height, min_width, min_height
+        );
+
+        // Vertically centered as far as wrapping allows
+        let [_, middle] =
+            Layout::vertical([Constraint::Percentage(50), Constraint::Min(0)]).areas(area);
+
+        Block::new()
+            .bg(self.config.colors.normal_row_color)
+            .render(area, buf);
+        Paragraph::new(text)
+            .fg(self.config.colors.text_color)
+            .centered()
+            .wrap(Wrap { trim: true })
+            .render(middle, buf);
+    }
+
     fn render_body(&mut self, area: Rect, buf: &mut Buffer) {
+        if self.layout == PaneLayout::Table {
+            self.items.render(&self.config, true, area, buf);
+            return;
+        }
+
         // Create a layout with 2 columns
-        let horizontal =
-            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]);
+        let horizontal = Layout::horizontal([
+            Constraint::Percentage(self.split_ratio),
+            Constraint::Percentage(100 - self.split_ratio),
+        ]);
+
+        let [left, mut right] = horizontal.areas(area);
 
-        let [left, right] = horizontal.areas(area);
+        self.items.render(&self.config, false, left, buf);
 
-        // Create two chunks with equal vertical screen space. One for the list and the other for
-        // the info block.
-        //let vertical = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]);
+        let mut image_preview = self.image_preview.take();
+        let mut readme_cache = self.readme_cache.take();
+        let mut diff = self.diff_stat.take();
 
-        //let [upper_item_list_area, input_area] = vertical.areas(left);
+        // The marked project gets a third pane when there is room for it
+        let current_key = self.items.current().map(Project::key);
+        let compare = self
+            .compare_key
+            .as_ref()
+            .filter(|key| Some(*key) != current_key && area.width >= COMPARE_MIN_WIDTH)
+            .and_then(|key| self.items.project(key));
 
-        self.items.render(&self.config, left, buf);
+        if let Some(project) = compare {
+            let [compare_area, current_area] =
+                Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(right);
+            right = current_area;
+
+            let readme = match &mut readme_cache {
+                Some(readme_cache) => readme_cache.get(project),
+                None => project.readme.as_deref(),
+            };
+            self.render_info(project, readme, None, None, compare_area, buf);
+        }
 
         if let Some(project) = self.items.current() {
-            self.render_info(project, right, buf);
+            let readme = match &mut readme_cache {
+                Some(readme_cache) => readme_cache.get(project),
+                None => project.readme.as_deref(),
+            };
+
+            let diff = if self.show_diff && project.is_dirty() {
+                if diff.as_ref().map(|(key, _)| key) != Some(project.key()) {
+                    self.spawn_diff_stat(project);
+                    diff = Some((project.key().clone(), None));
+                }
+                diff.as_ref()
+                    .map(|(_, stat)| stat.as_deref().unwrap_or(DIFF_LOADING_TEXT))
+            } else {
+                None
+            };
+
+            self.render_info(project, readme, diff, image_preview.as_mut(), right, buf);
         }
 
-        // TODO: Add this back when search is done properly
-        // Paragraph::new(self.search.as_str())
-        //     .style(Style::default().fg(Color::Yellow))
-        //     .render(input_area, buf);
+        self.image_preview = image_preview;
+        self.readme_cache = readme_cache;
+        self.diff_stat = diff;
     }
 
     fn render_footer(&mut self, area: Rect, buf: &mut Buffer) {
-        let info_footer = Paragraph::new(Line::from(INFO_TEXT))
+        let footer_text = if let Some(status) = &self.status {
+            status.clone()
+        } else if let Some(prompt) = &self.template_prompt {
+            format!(
+                "New project from {}: {} | {}",
+                prompt.template_name, prompt.input, TEMPLATE_INFO_TEXT
+            )
+        } else if let Some(prompt) = &self.tag_prompt {
+            format!(
+                "Tags for {}: {} | {}",
+                prompt.name, prompt.input, TAG_INFO_TEXT
+            )
+        } else if let Some(confirm) = self.confirming {
+            confirm.prompt()
+        } else if self.items.is_filtering() {
+            FILTER_INFO_TEXT.to_string()
+        } else if self.items.query().content {
+            CONTENT_SEARCH_INFO_TEXT.to_string()
+        } else if self.items.is_searching() {
+            SEARCH_INFO_TEXT.to_string()
+        } else {
+            let esc = if self.config.esc_quits() {
+                ""
+            } else {
+                " | (Esc) clear filter"
+            };
+            let scanning = match self.scanning {
+                Some(found) => format!("Scanning… ({} found) | ", found),
+                None => String::new(),
+            };
+            format!(
+                "{}({}) quit | (s) sort: {} | {}{}",
+                scanning,
+                self.config.quit_label(),
+                self.items.sort_key().label(),
+                INFO_TEXT,
+                esc
+            )
+        };
+
+        let info_footer = Paragraph::new(Line::from(footer_text))
             .style(
                 Style::new()
                     .fg(self.config.colors.text_color)
@@ -230,19 +1358,49 @@ impl App {
         //f.render_widget(info_footer, area);
     }
 
-    fn render_info(&self, project: &Project, area: Rect, buf: &mut Buffer) {
-        // We get the info depending on the item's state.
-        let info = format!(
-            "{}\n{}",
-            project.name,
-            project.readme.as_deref().unwrap_or(""),
-        );
+    fn render_info(
+        &self,
+        project: &Project,
+        readme: Option<&str>,
+        diff: Option<&str>,
+        image_preview: Option<&mut ImagePreview>,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        let mut info = Text::default();
+        for section in &self.info_sections {
+            let text = match (section, diff, readme) {
+                (InfoSection::Readme, None, Some(readme))
+                    if self.config.render_markdown()
+                        && !readme.trim().is_empty()
+                        && project.readme_path.as_deref().is_some_and(is_markdown) =>
+                {
+                    markdown::to_text(readme, self.config.colors.markdown_code_fg)
+                }
+                _ => match section.text(&self.config, project, readme, diff) {
+                    Some(text) => Text::raw(text),
+                    None => continue,
+                },
+            };
+            info.lines.extend(text.lines);
+        }
+
+        // Name the readme file found, as projects use all sorts of names for it
+        let readme_name = project
+            .readme_path
+            .as_deref()
+            .and_then(Path::file_name)
+            .filter(|_| diff.is_none() && self.info_sections.contains(&InfoSection::Readme));
+        let title = match readme_name {
+            Some(readme_name) => format!("{} · {}", project.name, readme_name.to_string_lossy()),
+            None => project.name.clone(),
+        };
 
         // We show the list item's info under the list in this paragraph
         let outer_info_block = Block::new()
             .borders(Borders::NONE)
             .title_alignment(Alignment::Center)
-            .title(project.name.as_str())
+            .title(title)
             .fg(self.config.colors.text_color)
             .bg(self.config.colors.project_header_bg);
 
@@ -254,11 +1412,16 @@ impl App {
         // This is a similar process to what we did for list. outer_info_area will be used for
         // header inner_info_area will be used for the list info.
         let outer_info_area = area;
-        let inner_info_area = outer_info_block.inner(outer_info_area);
+        let mut inner_info_area = outer_info_block.inner(outer_info_area);
 
         // We can render the header. Inner info will be rendered later
         outer_info_block.render(outer_info_area, buf);
 
+        if let Some(image_preview) = image_preview {
+            inner_info_area =
+                image_preview.render(project, readme, &self.background_tx, inner_info_area, buf);
+        }
+
         let info_paragraph = Paragraph::new(info)
             .block(inner_info_block)
             .fg(self.config.colors.text_color)
@@ -268,3 +1431,119 @@ impl App {
         info_paragraph.render(inner_info_area, buf);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_dir;
+
+    #[test]
+    fn test_quit_key_sequences() {
+        let press = |quit_key: QuitKey, keys: &[KeyCode]| {
+            keys.iter()
+                .fold(0, |pressed, code| quit_key.advance(pressed, *code))
+        };
+
+        assert_eq!(press(QuitKey::Esc, &[KeyCode::Esc]), 1);
+        assert_eq!(press(QuitKey::Q, &[KeyCode::Esc]), 0);
+
+        let z = KeyCode::Char('Z');
+        assert_eq!(press(QuitKey::ZZ, &[z]), 1);
+        assert_eq!(press(QuitKey::ZZ, &[z, z]), 2);
+        assert_eq!(press(QuitKey::ZZ, &[z, KeyCode::Char('j'), z]), 1);
+
+        let colon = KeyCode::Char(':');
+        let q = KeyCode::Char('q');
+        assert_eq!(press(QuitKey::ColonQ, &[colon, q]), 2);
+        // A repeated first key starts the sequence again
+        assert_eq!(press(QuitKey::ColonQ, &[colon, colon, q]), 2);
+        assert_eq!(press(QuitKey::ColonQ, &[q]), 0);
+    }
+
+    #[tokio::test]
+    async fn test_draw_layout() {
+        let config = Arc::new(Config::default());
+        let loader = ProjectLoader::new(config.clone(), None).unwrap();
+        let mut app = App::new(config, loader);
+
+        let mut terminal = Terminal::new(backend::TestBackend::new(60, 10)).unwrap();
+        app.draw(&mut terminal).unwrap();
+
+        // Only the text is compared, colors are covered by the project table tests
+        let lines = |terminal: &Terminal<backend::TestBackend>| {
+            let buf = terminal.backend().buffer();
+            (0..buf.area.height)
+                .map(|y| (0..buf.area.width).map(|x| buf[(x, y)].symbol()).collect())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(
+            lines(&terminal),
+            [
+                "           Projects                                         ",
+                "Name         Modified ↓      │                              ",
+                "                             │                              ",
+                "                             │                              ",
+                "                             │                              ",
+                "                             │                              ",
+                "                             │                              ",
+                "╔══════════════════════════════════════════════════════════╗",
+                "║Scanning… (0 found) | (Esc) quit | (s) sort: modified | (↑║",
+                "╚══════════════════════════════════════════════════════════╝",
+            ]
+        );
+
+        app.scanning = None;
+        app.draw(&mut terminal).unwrap();
+        assert_eq!(
+            lines(&terminal)[8],
+            "║(Esc) quit | (s) sort: modified | (↑/↓) move | (o) open | ║"
+        );
+
+        // The help is drawn over the body, leaving the footer
+        app.show_help = true;
+        app.draw(&mut terminal).unwrap();
+        let help = lines(&terminal);
+        assert!(help[0].contains("Keys"));
+        assert!(help[1].contains(" Esc ") && help[1].contains("Quit"));
+        assert!(help[6].contains("(?/Esc) close"));
+        assert!(help[8].starts_with("║(Esc) quit"));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_waits_for_dialogs() {
+        let config = Arc::new(Config::default());
+        let loader = ProjectLoader::new(config.clone(), None).unwrap();
+        let mut app = App::new(config, loader);
+
+        app.scanning = None;
+        app.confirming = Some(Confirm::Quit);
+        app.refresh();
+        assert_eq!(app.scanning, None);
+
+        app.confirming = None;
+        app.refresh();
+        assert_eq!(app.scanning, Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_diff_stat_in_background() {
+        let dir = test_dir("background-diff");
+        git2::Repository::init(&dir).unwrap();
+        std::fs::write(dir.join("new.txt"), "untracked").unwrap();
+
+        let config = Arc::new(Config::default());
+        let loader = ProjectLoader::new(config.clone(), None).unwrap();
+        let mut app = App::new(config.clone(), loader);
+        let project = Project::from_path(&config, dir.clone(), 0).unwrap();
+
+        app.diff_stat = Some((project.key().clone(), None));
+        app.spawn_diff_stat(&project);
+        let done = app.background_rx.recv().await.unwrap();
+        app.finish_background(done);
+
+        let (_, stat) = app.diff_stat.as_ref().unwrap();
+        assert!(stat.as_ref().unwrap().contains("new.txt"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
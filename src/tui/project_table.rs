@@ -1,66 +1,354 @@
-use chrono::{DateTime, Local, TimeDelta};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
 use crossterm::event::{Event, KeyCode, KeyEventKind};
 use eyre::Result;
-use fancy_duration::FancyDuration;
 use ratatui::{prelude::*, widgets::*};
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+use unicode_width::UnicodeWidthChar;
 
 use crate::{
     config::Config,
-    project::{Project, ProjectEvent, ProjectStore},
+    project::{
+        format_size, Filter, Pins, Project, ProjectEvent, ProjectKey, ProjectStore, SearchQuery,
+        SortKey, Tags,
+    },
+    tui::{
+        filter_popup::{FilterPopup, FilterPopupOutcome},
+        keymap::{Action, KeyMap},
+    },
 };
 
+/// How long projects found for the first time by a rescan stay highlighted
+const NEW_PROJECT_HIGHLIGHT: Duration = Duration::from_secs(5);
+
+/// Shown after the names of projects with uncommitted changes
+const DIRTY_MARKER: &str = " ●";
+
+/// Shown before the names of pinned projects
+const PIN_MARKER: &str = "★ ";
+
+/// Keys that start a two key sequence, pressing one waits to see if the same key follows
+const SEQUENCE_KEYS: &[char] = &['g', 'd'];
+
+/// What `h`/Left does in the project list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeftAction {
+    /// Clear the selection, remembering it for the next move
+    #[default]
+    Unselect,
+    /// Do nothing
+    None,
+}
+
+/// Name column cell cut down to `width`, colored by kind and marked if the project is
+/// pinned or has uncommitted changes
+fn name_cell(
+    config: &Config,
+    project: &Project,
+    pinned: bool,
+    text: &str,
+    width: usize,
+) -> Cell<'static> {
+    let marker = if project.is_dirty() { DIRTY_MARKER } else { "" };
+    let pin = if pinned { PIN_MARKER } else { "" };
+
+    // Keep the workspace member indent whichever end is cut
+    let name = text.trim_start_matches(' ');
+    let indent = &text[..text.len() - name.len()];
+    let name = config.name_truncation.truncate(
+        name,
+        width.saturating_sub(indent.len() + pin.chars().count() + marker.chars().count()),
+    );
+
+    let line = Line::from(vec![
+        Span::raw(format!("{}{}{}", indent, pin, name)),
+        Span::styled(marker, Style::new().fg(config.colors.dirty_marker_fg)),
+    ]);
+    let color = project
+        .kind
+        .as_ref()
+        .and_then(|kind| config.colors.kind_color(kind));
+    match color {
+        Some(color) => Cell::from(line).fg(color),
+        None => Cell::from(line),
+    }
+}
+
+/// Which part of a project name too long for the name column is replaced with `…`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NameTruncation {
+    /// Keep the end of the name, `…ry-long-service`
+    Start,
+    /// Keep both ends of the name, `my-very-lo…-service`
+    #[default]
+    Middle,
+    /// Keep the start of the name, `my-very-long-se…`
+    End,
+}
+
+impl NameTruncation {
+    /// `name` cut down to at most `width` columns
+    fn truncate(self, name: &str, width: usize) -> String {
+        let name_width: usize = name.chars().map(char_width).sum();
+        if name_width <= width {
+            return name.to_string();
+        }
+        if width == 0 {
+            return String::new();
+        }
+
+        // One column goes to the ellipsis
+        let kept = width - 1;
+        let (head, tail) = match self {
+            NameTruncation::Start => (0, kept),
+            NameTruncation::Middle => (kept.div_ceil(2), kept / 2),
+            NameTruncation::End => (kept, 0),
+        };
+
+        let mut truncated = take_width(name.chars(), head);
+        truncated.push('…');
+        let end: String = take_width(name.chars().rev(), tail).chars().rev().collect();
+        truncated.push_str(&end);
+        truncated
+    }
+}
+
+fn char_width(c: char) -> usize {
+    c.width().unwrap_or(0)
+}
+
+/// Leading `chars` that fit in `width` columns, a wide character that would straddle
+/// the limit is left out
+fn take_width(chars: impl Iterator<Item = char>, width: usize) -> String {
+    let mut used = 0;
+    chars
+        .take_while(|&c| {
+            used += char_width(c);
+            used <= width
+        })
+        .collect()
+}
+
 #[derive(Default)]
 pub(crate) struct ProjectTable {
     state: TableState,
     items: ProjectStore,
     last_selected: Option<usize>,
+    left_action: LeftAction,
+    /// Moving past either end of the list continues from the other end
+    wrap_navigation: bool,
+    /// Text typed into the search bar
+    search_input: String,
+    /// Whether keys are currently being typed into the search bar
+    searching: bool,
+    /// Show modified times as timestamps rather than relative to now
+    absolute_times: bool,
+    /// Filter being edited, while open it takes all key presses
+    filter_popup: Option<FilterPopup>,
+    /// First key of a possible sequence and when it times out
+    pending_key: Option<(char, Instant)>,
+    sequence_timeout: Duration,
+    /// Every project any scan has found
+    known: HashSet<ProjectKey>,
+    /// Whether the first scan has finished, projects found after it by rescans or the
+    /// watcher are new
+    scanned: bool,
+    /// Projects first found after the first scan and when, highlighted for a while
+    new_projects: HashMap<ProjectKey, Instant>,
+    keymap: KeyMap,
 }
 
 impl ProjectTable {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(config: &Config) -> Self {
         Self {
             state: TableState::default(),
-            items: ProjectStore::default(),
+            items: ProjectStore::new(config.sort).with_health(config.health),
             last_selected: None,
+            left_action: config.left_action,
+            wrap_navigation: config.wrap_navigation(),
+            search_input: String::new(),
+            searching: false,
+            absolute_times: false,
+            filter_popup: None,
+            pending_key: None,
+            sequence_timeout: config.key_sequence_timeout(),
+            known: HashSet::new(),
+            scanned: false,
+            new_projects: HashMap::new(),
+            keymap: config.keybindings.clone(),
+        }
+    }
+
+    /// List the projects in `pins` first
+    pub(crate) fn with_pins(self, pins: Pins) -> Self {
+        Self {
+            items: self.items.with_pins(pins),
+            ..self
+        }
+    }
+
+    /// Give projects the tags in `tags`
+    pub(crate) fn with_tags(self, tags: Tags) -> Self {
+        Self {
+            items: self.items.with_tags(tags),
+            ..self
+        }
+    }
+
+    pub(crate) fn is_searching(&self) -> bool {
+        self.searching
+    }
+
+    pub(crate) fn is_filtering(&self) -> bool {
+        self.filter_popup.is_some()
+    }
+
+    fn open_filter(&mut self) {
+        self.filter_popup = Some(FilterPopup::new(
+            self.items.filter().clone(),
+            self.search_input.clone(),
+            self.items.kinds(),
+        ));
+    }
+
+    fn handle_filter_input(&mut self, code: KeyCode) {
+        let Some(popup) = &mut self.filter_popup else {
+            return;
+        };
+
+        match popup.handle_input(code) {
+            FilterPopupOutcome::Editing => {}
+            FilterPopupOutcome::Cancel => self.filter_popup = None,
+            FilterPopupOutcome::Apply => {
+                if let Some(popup) = self.filter_popup.take() {
+                    let (filter, search_input) = popup.into_filter();
+                    self.items.set_filter(filter);
+                    self.search_input = search_input;
+                    self.state
+                        .select(if self.items.is_empty() { None } else { Some(0) });
+                }
+            }
+        }
+    }
+
+    /// Clear the search and filter, or the selection when there is neither
+    pub(crate) fn clear_filter(&mut self) {
+        if self.search_input.is_empty() && *self.items.filter() == Filter::default() {
+            self.left();
+            return;
+        }
+
+        self.search_input.clear();
+        self.items.set_filter(Filter::default());
+        self.state
+            .select(if self.items.is_empty() { None } else { Some(0) });
+    }
+
+    pub(crate) fn query(&self) -> &SearchQuery {
+        self.items.query()
+    }
+
+    fn set_search_input(&mut self, input: String) {
+        self.items.set_query(SearchQuery::parse(&input));
+        self.search_input = input;
+        self.state.select(self.items.best_match());
+    }
+
+    fn handle_search_input(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => {
+                self.searching = false;
+                self.set_search_input(String::new());
+            }
+            KeyCode::Enter => {
+                self.searching = false;
+            }
+            KeyCode::Backspace => {
+                let mut input = self.search_input.clone();
+                input.pop();
+                self.set_search_input(input);
+            }
+            KeyCode::Char(c) => {
+                let mut input = self.search_input.clone();
+                input.push(c);
+                self.set_search_input(input);
+            }
+            KeyCode::Down => self.next(),
+            KeyCode::Up => self.previous(),
+            _ => {}
+        }
+    }
+
+    fn left(&mut self) {
+        match self.left_action {
+            LeftAction::Unselect => self.unselect(),
+            LeftAction::None => {}
         }
     }
 
     fn go_top(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
         self.state.select(Some(0));
     }
 
     fn go_bottom(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
         self.state.select(Some(self.items.len() - 1));
     }
 
     fn next(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
-                if i >= self.items.len() - 1 {
+                if i < self.items.len() - 1 {
+                    i + 1
+                } else if self.wrap_navigation {
                     0
                 } else {
-                    i + 1
+                    i
                 }
             }
-            None => self.last_selected.unwrap_or(0),
+            None => self.reselect(),
         };
         self.state.select(Some(i));
     }
 
     fn previous(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
-                if i == 0 {
+                if i > 0 {
+                    i - 1
+                } else if self.wrap_navigation {
                     self.items.len() - 1
                 } else {
-                    i - 1
+                    i
                 }
             }
-            None => self.last_selected.unwrap_or(0),
+            None => self.reselect(),
         };
         self.state.select(Some(i));
     }
 
+    /// Position to select after moving without a selection, where the last one was while
+    /// it is still in the list. Only called with projects listed.
+    fn reselect(&self) -> usize {
+        self.last_selected
+            .map_or(0, |i| i.min(self.items.len() - 1))
+    }
+
     fn unselect(&mut self) {
         let offset = self.state.offset();
         self.last_selected = self.state.selected();
@@ -68,36 +356,245 @@ impl ProjectTable {
         *self.state.offset_mut() = offset;
     }
 
+    /// Projects currently listed, i.e. matching the filter
+    pub(crate) fn shown(&self) -> impl Iterator<Item = &Project> {
+        self.items.iter()
+    }
+
+    pub(crate) fn project(&self, key: &ProjectKey) -> Option<&Project> {
+        self.items.get_by_key(key)
+    }
+
     pub(crate) fn current(&self) -> Option<&Project> {
-        self.state.selected().map(|i| &self.items[i])
+        self.state.selected().and_then(|i| self.items.get(i))
+    }
+
+    /// Bump the selected project's modified time to now, keeping it selected as it moves
+    pub(crate) fn touch_current(&mut self) -> Result<()> {
+        let Some(key) = self.current().map(|project| project.key().clone()) else {
+            return Ok(());
+        };
+
+        if let Some(project) = self.items.get_mut(&key) {
+            project.touch()?;
+        }
+
+        self.items.sort();
+        self.state.select(self.items.position(&key));
+        Ok(())
+    }
+
+    /// Pin the selected project to the top or unpin it, keeping it selected as it moves.
+    /// Returns its name and whether it is now pinned.
+    pub(crate) fn toggle_pin_current(&mut self) -> Result<Option<(String, bool)>> {
+        let Some((key, name)) = self
+            .current()
+            .map(|project| (project.key().clone(), project.name.clone()))
+        else {
+            return Ok(None);
+        };
+
+        let pinned = self.items.toggle_pin(&key)?;
+        self.state.select(self.items.position(&key));
+        Ok(Some((name, pinned)))
+    }
+
+    /// Replace the project's tags, keeping it selected unless a tag search now hides it
+    pub(crate) fn set_tags(&mut self, key: &ProjectKey, tags: Vec<String>) -> Result<()> {
+        self.items.set_tags(key, tags)?;
+        self.state.select(self.items.position(key));
+        Ok(())
+    }
+
+    pub(crate) fn sort_key(&self) -> SortKey {
+        self.items.sort_key()
+    }
+
+    /// Switch to the next sort key, keeping the selected project selected
+    fn cycle_sort(&mut self) {
+        let key = self.current().map(|project| project.key().clone());
+        self.items.sort_by(self.items.sort_key().next());
+        if let Some(key) = key {
+            self.state.select(self.items.position(&key));
+        }
+    }
+
+    /// Prepare for a rescan, dropping deleted projects
+    pub(crate) fn begin_rescan(&mut self) {
+        self.items.remove_missing();
+        if self.state.selected().is_some_and(|i| i >= self.items.len()) {
+            self.state.select(self.items.len().checked_sub(1));
+        }
+    }
+
+    /// Drop the selected project from the list until the next rescan
+    fn remove_current(&mut self) {
+        let Some(key) = self.current().map(|project| project.key().clone()) else {
+            return;
+        };
+
+        self.items.remove(&key);
+        if self.state.selected().is_some_and(|i| i >= self.items.len()) {
+            self.state.select(self.items.len().checked_sub(1));
+        }
+    }
+
+    /// When the pending first key of a sequence times out
+    pub(crate) fn pending_key_deadline(&self) -> Option<Instant> {
+        self.pending_key.map(|(_, deadline)| deadline)
+    }
+
+    /// Run the single key action of a pending key whose sequence was never finished
+    pub(crate) fn flush_pending_key(&mut self) {
+        if let Some((key, _)) = self.pending_key.take() {
+            self.single_key(key);
+        }
+    }
+
+    fn single_key(&mut self, key: char) {
+        if key == 'g' {
+            self.go_top();
+        }
+    }
+
+    fn key_sequence(&mut self, first: char, second: char) {
+        match (first, second) {
+            ('g', 'g') => self.go_top(),
+            ('d', 'd') => self.remove_current(),
+            _ => {}
+        }
+    }
+
+    /// Feed a key press through the sequence state, returning it if it still needs
+    /// handling as an ordinary key
+    fn sequence(&mut self, code: KeyCode) -> Option<KeyCode> {
+        let KeyCode::Char(c) = code else {
+            self.flush_pending_key();
+            return Some(code);
+        };
+
+        match self.pending_key.take() {
+            Some((first, deadline)) if first == c && Instant::now() <= deadline => {
+                self.key_sequence(first, c);
+                None
+            }
+            pending => {
+                if let Some((first, _)) = pending {
+                    self.single_key(first);
+                }
+
+                if SEQUENCE_KEYS.contains(&c) {
+                    self.pending_key = Some((c, Instant::now() + self.sequence_timeout));
+                    None
+                } else {
+                    Some(code)
+                }
+            }
+        }
     }
 
     pub(crate) fn update(&mut self, event: ProjectEvent) -> Result<()> {
+        match &event {
+            ProjectEvent::Add(project) => {
+                let key = project.key().clone();
+                if self.known.insert(key.clone()) && self.scanned {
+                    self.new_projects.insert(key, Instant::now());
+                }
+            }
+            ProjectEvent::Scanned => self.scanned = true,
+            _ => {}
+        }
+
         self.items.update(event)
     }
 
+    /// When the oldest new project highlight should fade
+    pub(crate) fn new_project_deadline(&self) -> Option<Instant> {
+        self.new_projects
+            .values()
+            .min()
+            .map(|found| *found + NEW_PROJECT_HIGHLIGHT)
+    }
+
+    /// Stop highlighting projects that have been new for long enough
+    pub(crate) fn fade_new_projects(&mut self) {
+        let now = Instant::now();
+        self.new_projects
+            .retain(|_, found| now < *found + NEW_PROJECT_HIGHLIGHT);
+    }
+
     pub(crate) async fn handle_input(&mut self, event: Event) -> Result<()> {
         match event {
-            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
-                KeyCode::Char('h') | KeyCode::Left => self.unselect(),
-                KeyCode::Char('j') | KeyCode::Down => self.next(),
-                KeyCode::Char('k') | KeyCode::Up => self.previous(),
-                KeyCode::Char('g') | KeyCode::Home => self.go_top(),
-                KeyCode::Char('G') | KeyCode::End => self.go_bottom(),
-                _ => {}
-            },
+            Event::Key(key) if key.kind == KeyEventKind::Press && self.is_filtering() => {
+                self.handle_filter_input(key.code)
+            }
+            Event::Key(key) if key.kind == KeyEventKind::Press && self.searching => {
+                self.handle_search_input(key.code)
+            }
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                // Keys bound in the keymap act straight away, even ones that would
+                // otherwise start a sequence
+                let action = self.keymap.action(&key);
+                let code = if action.is_some() {
+                    self.flush_pending_key();
+                    key.code
+                } else {
+                    let Some(code) = self.sequence(key.code) else {
+                        return Ok(());
+                    };
+                    code
+                };
+
+                match action {
+                    Some(Action::Up) => self.previous(),
+                    Some(Action::Down) => self.next(),
+                    Some(Action::Top) => self.go_top(),
+                    Some(Action::Bottom) => self.go_bottom(),
+                    Some(Action::Unselect) => self.left(),
+                    Some(Action::Open | Action::Quit) => {}
+                    None => self.handle_key(code),
+                }
+            }
             _ => {}
         }
 
         Ok(())
     }
 
-    pub(crate) fn render(&mut self, config: &Config, area: Rect, buf: &mut Buffer) {
+    /// Keys that can't be rebound
+    fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('/') => self.searching = true,
+            KeyCode::Char('f') => self.open_filter(),
+            KeyCode::Char('T') => self.absolute_times = !self.absolute_times,
+            KeyCode::Char('s') => self.cycle_sort(),
+            _ => {}
+        }
+    }
+
+    /// Render the project list, `extended` adds columns for details otherwise only
+    /// shown in the info pane
+    pub(crate) fn render(&mut self, config: &Config, extended: bool, area: Rect, buf: &mut Buffer) {
         // We create two blocks, one is for the header (outer) and the other is for list (inner).
+        // Sizes arrive as projects are walked so the total grows during a scan
+        let total_size = self.items.total_size();
+        let mut title = if total_size > 0 {
+            format!("Projects · {}", format_size(total_size))
+        } else {
+            "Projects".to_string()
+        };
+        if config.roots_discovered {
+            let roots: Vec<_> = config
+                .roots()
+                .map(|dir| dir.display_name(config.full_paths))
+                .collect();
+            title.push_str(&format!(" · auto-detected {}", roots.join(", ")));
+        }
+
         let outer_block = Block::new()
             .borders(Borders::NONE)
             .title_alignment(Alignment::Center)
-            .title("Projects")
+            .title(title)
             .fg(config.colors.text_color)
             .bg(config.colors.project_header_bg);
         let inner_block = Block::new()
@@ -107,11 +604,18 @@ impl ProjectTable {
 
         // We get the inner area from outer_block. We'll use this area later to render the table.
         let outer_area = area;
-        let inner_area = outer_block.inner(outer_area);
+        let mut inner_area = outer_block.inner(outer_area);
 
         // We can render the header in outer_area.
         outer_block.render(outer_area, buf);
 
+        if self.searching || !self.search_input.is_empty() || self.items.filter().has_predicates() {
+            let [table_area, search_area] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(inner_area);
+            inner_area = table_area;
+            self.render_search(config, search_area, buf);
+        }
+
         // Iterate through all elements in the `items` and stylize them.
         // let items: Vec<ListItem> = self
         //     .items
@@ -138,46 +642,52 @@ impl ProjectTable {
         //     .highlight_symbol(">")
         //     .highlight_spacing(HighlightSpacing::Always);
 
+        let sort_key = self.items.sort_key();
+        let columns = config.columns.shown(extended);
+        let name_width = self.name_width(&columns, inner_block.inner(inner_area));
+
         let rows: Vec<Row> = self
             .items
             .iter()
-            .map(|project| {
-                Row::new(vec![project.name.clone(), {
-                    let now: DateTime<Local> = Local::now();
-                    let date: DateTime<Local> = project.modified.into();
-                    let d = now.signed_duration_since(date);
-
-                    if d.abs() < TimeDelta::new(60, 0).unwrap() {
-                        "just now".to_string()
-                    } else if d.abs() > TimeDelta::new(48 * 60 * 60, 0).unwrap() {
-                        let date: DateTime<Local> = project.modified.into();
-                        date.format("%Y-%m-%d").to_string()
-                    } else if d >= TimeDelta::zero() {
-                        format!(
-                            "{} ago",
-                            FancyDuration::new(d).filter(&[
-                                fancy_duration::DurationPart::Days,
-                                fancy_duration::DurationPart::Hours,
-                                fancy_duration::DurationPart::Minutes,
-                            ])
-                        )
+            .enumerate()
+            .map(|(idx, project)| {
+                let cells = columns.iter().map(|column| {
+                    let text = column.cell(project, self.absolute_times);
+                    if *column == Column::Name {
+                        let pinned = self.items.is_pinned(project);
+                        name_cell(config, project, pinned, &text, name_width)
                     } else {
-                        format!(
-                            "{} from now",
-                            FancyDuration::new(d.abs()).filter(&[
-                                fancy_duration::DurationPart::Days,
-                                fancy_duration::DurationPart::Hours,
-                                fancy_duration::DurationPart::Minutes,
-                            ])
-                        )
+                        Cell::from(text)
                     }
-                }])
+                });
+
+                let mut row = Row::new(cells);
+                if project.is_bare() {
+                    row = row.italic();
+                }
+
+                if self.new_projects.contains_key(project.key()) {
+                    row.bg(config.colors.new_project_bg)
+                } else if !config.striped_rows {
+                    row
+                } else if idx % 2 == 0 {
+                    row.bg(config.colors.even_row_color)
+                } else {
+                    row.bg(config.colors.odd_row_color)
+                }
             })
             .collect();
 
-        //let rows = [Row::new(vec!["Cell1", "Cell2"])];
-        // Columns widths are constrained in the same way as Layout...
-        let widths = [Constraint::Fill(1), Constraint::Length(16)];
+        // The name column takes whatever width the others leave
+        let widths = columns.iter().map(|column| column.width());
+
+        // Tables keep their header in place as the rows scroll
+        let header = Row::new(columns.iter().map(|column| column.title(sort_key))).style(
+            Style::new()
+                .fg(config.colors.column_header_fg)
+                .bg(config.colors.project_header_bg)
+                .add_modifier(Modifier::BOLD),
+        );
 
         let table = Table::new(rows, widths)
             // ...and they can be separated by a fixed spacing.
@@ -185,6 +695,7 @@ impl ProjectTable {
             // You can set the style of the entire Table.
             .style(Style::new().blue())
             // It has an optional header, which is simply a Row always visible at the top.
+            .header(header)
             // It has an optional footer, which is simply a Row always visible at the bottom.
             //.footer(Row::new(vec!["Updated on Dec 28"]))
             // .footer(
@@ -204,5 +715,434 @@ impl ProjectTable {
         // (look careful we are using StatefulWidget's render.)
         // ratatui::widgets::StatefulWidget::render as stateful_render
         StatefulWidget::render(table, inner_area, buf, &mut self.state);
+        self.render_scrollbar(config, inner_area, buf);
+    }
+
+    /// Scrollbar over the right border beside the rows, only when they don't all fit
+    fn render_scrollbar(&self, config: &Config, area: Rect, buf: &mut Buffer) {
+        // Below the header row
+        let rows_area = Rect {
+            y: area.y + 1,
+            height: area.height.saturating_sub(1),
+            ..area
+        };
+        let len = self.items.len();
+        if len <= usize::from(rows_area.height) {
+            return;
+        }
+
+        let mut state = ScrollbarState::new(len)
+            .viewport_content_length(usize::from(rows_area.height))
+            .position(self.state.selected().unwrap_or(self.state.offset()));
+        Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None)
+            .track_symbol(Some(symbols::line::VERTICAL))
+            .style(Style::new().fg(config.colors.text_color))
+            .render(rows_area, buf, &mut state);
+    }
+
+    /// Width of the name column when the table is drawn in `area`, laid out the same
+    /// way `Table` lays out its columns
+    fn name_width(&self, columns: &[Column], area: Rect) -> usize {
+        // The ">" highlight symbol takes a column only while a row is selected
+        let selection_width = u16::from(self.state.selected().is_some());
+        let area = Rect {
+            width: area.width.saturating_sub(selection_width),
+            ..area
+        };
+        let widths = Layout::horizontal(columns.iter().map(|column| column.width()))
+            .flex(layout::Flex::Start)
+            .spacing(1)
+            .split(area);
+        widths[0].width as usize
+    }
+
+    /// Render the filter popup over `area` if it is open
+    pub(crate) fn render_filter_popup(&self, config: &Config, area: Rect, buf: &mut Buffer) {
+        if let Some(popup) = &self.filter_popup {
+            popup.render(config, area, buf);
+        }
+    }
+
+    fn render_search(&self, config: &Config, area: Rect, buf: &mut Buffer) {
+        let mut spans = vec![Span::raw("/"), Span::raw(self.search_input.as_str())];
+
+        if self.searching {
+            spans.push(Span::raw(" ").reversed());
+        }
+
+        let filter = self.items.filter();
+        if filter.has_predicates() {
+            spans.push(Span::raw(format!(" [{}]", filter.describe())));
+        }
+
+        Paragraph::new(Line::from(spans))
+            .style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .bg(config.colors.normal_row_color),
+            )
+            .render(area, buf);
+    }
+}
+
+/// Optional columns of the project list, the name is always shown
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColumnConfig {
+    modified: bool,
+    /// Only shown in the table layout
+    file_count: bool,
+    /// Disk used by the files counted, only shown in the table layout
+    size: bool,
+    /// Only shown in the table layout
+    branch: bool,
+    /// Only shown in the table layout
+    kind: bool,
+}
+
+impl Default for ColumnConfig {
+    fn default() -> Self {
+        Self {
+            modified: true,
+            file_count: true,
+            size: true,
+            branch: true,
+            kind: true,
+        }
+    }
+}
+
+impl ColumnConfig {
+    /// Columns to show in order, `extended` being the full width table layout
+    fn shown(&self, extended: bool) -> Vec<Column> {
+        [
+            (Column::Name, true),
+            (Column::Modified, self.modified),
+            (Column::FileCount, extended && self.file_count),
+            (Column::Size, extended && self.size),
+            (Column::Branch, extended && self.branch),
+            (Column::Kind, extended && self.kind),
+        ]
+        .into_iter()
+        .filter_map(|(column, shown)| shown.then_some(column))
+        .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    Name,
+    Modified,
+    FileCount,
+    Size,
+    Branch,
+    Kind,
+}
+
+impl Column {
+    fn width(self) -> Constraint {
+        match self {
+            Column::Name => Constraint::Fill(1),
+            Column::Modified => Constraint::Length(16),
+            Column::FileCount => Constraint::Length(8),
+            Column::Size => Constraint::Length(10),
+            Column::Branch => Constraint::Length(28),
+            Column::Kind => Constraint::Length(12),
+        }
+    }
+
+    /// Header text, with the sort direction on the column projects are sorted by
+    fn title(self, sort_key: SortKey) -> String {
+        let (title, sorted_by) = match self {
+            Column::Name => ("Name", Some(SortKey::Name)),
+            Column::Modified => ("Modified", Some(SortKey::Modified)),
+            Column::FileCount => ("Files", Some(SortKey::FileCount)),
+            Column::Size => ("Size", None),
+            Column::Branch => ("Branch", None),
+            Column::Kind => ("Kind", None),
+        };
+
+        if sorted_by == Some(sort_key) {
+            format!("{} {}", title, sort_key.indicator())
+        } else {
+            title.to_string()
+        }
+    }
+
+    fn cell(self, project: &Project, absolute_times: bool) -> String {
+        match self {
+            // Workspace members are indented under their workspace
+            Column::Name if project.parent.is_some() => format!("  {}", project.name),
+            Column::Name => project.name.clone(),
+            Column::Modified => project.format_modified(absolute_times),
+            Column::FileCount => project.format_file_count(),
+            Column::Size => project.format_size(),
+            Column::Branch => project.branch_summary().unwrap_or_default(),
+            Column::Kind => project
+                .kind
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    use crossterm::event::KeyEvent;
+
+    use super::*;
+    use crate::project::{test_project, GitStatus, ProjectKind};
+
+    fn project(name: &str) -> Project {
+        test_project(&format!("/projects/{}", name), SystemTime::UNIX_EPOCH)
+    }
+
+    fn table(names: &[&str]) -> ProjectTable {
+        let mut table = ProjectTable::new(&Config::default());
+        for name in names {
+            table.items.add(project(name));
+        }
+        table
+    }
+
+    #[test]
+    fn test_wrap_navigation() {
+        let mut table = table(&["a", "b"]);
+        assert!(table.wrap_navigation);
+        table.state.select(Some(1));
+        table.next();
+        assert_eq!(table.state.selected(), Some(0));
+        table.previous();
+        assert_eq!(table.state.selected(), Some(1));
+
+        table.wrap_navigation = false;
+        table.next();
+        assert_eq!(table.state.selected(), Some(1));
+        table.state.select(Some(0));
+        table.previous();
+        assert_eq!(table.state.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_navigation_without_projects() {
+        let mut empty = table(&[]);
+        empty.next();
+        empty.previous();
+        empty.go_top();
+        empty.go_bottom();
+        assert_eq!(empty.state.selected(), None);
+
+        // The last selection may be past the end once the list has shrunk
+        let mut table = table(&["a", "b", "c"]);
+        table.state.select(Some(2));
+        table.unselect();
+        table.items.set_query(SearchQuery::parse("a"));
+        table.next();
+        assert_eq!(table.state.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_key_sequences() {
+        let mut table = table(&["a", "b", "c"]);
+        table.state.select(Some(2));
+
+        assert_eq!(table.sequence(KeyCode::Char('g')), None);
+        assert_eq!(table.state.selected(), Some(2));
+        assert_eq!(table.sequence(KeyCode::Char('g')), None);
+        assert_eq!(table.state.selected(), Some(0));
+
+        // dd hides the selected project
+        assert_eq!(table.sequence(KeyCode::Char('d')), None);
+        assert_eq!(table.sequence(KeyCode::Char('d')), None);
+        assert_eq!(table.items.len(), 2);
+        assert_eq!(table.current().unwrap().name, "b");
+
+        // Another key ends the sequence, running the single key action first
+        table.state.select(Some(1));
+        table.sequence(KeyCode::Char('g'));
+        assert_eq!(table.sequence(KeyCode::Char('j')), Some(KeyCode::Char('j')));
+        assert_eq!(table.state.selected(), Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_rebound_sequence_key() {
+        let mut table = table(&["a", "b", "c"]);
+        table.keymap = toml::from_str(r#"down = "d""#).unwrap();
+        table.state.select(Some(0));
+
+        let press = |c| Event::Key(KeyEvent::from(KeyCode::Char(c)));
+        table.handle_input(press('d')).await.unwrap();
+        assert_eq!(table.state.selected(), Some(1));
+        assert_eq!(table.pending_key_deadline(), None);
+
+        // Keys left unbound still start sequences
+        table.handle_input(press('g')).await.unwrap();
+        table.handle_input(press('g')).await.unwrap();
+        assert_eq!(table.state.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_key_sequence_timeout() {
+        let mut table = table(&["a", "b"]);
+        table.sequence_timeout = Duration::ZERO;
+        table.state.select(Some(1));
+
+        table.sequence(KeyCode::Char('d'));
+        std::thread::sleep(Duration::from_millis(1));
+        table.sequence(KeyCode::Char('d'));
+        assert_eq!(table.items.len(), 2);
+
+        table.flush_pending_key();
+        assert_eq!(table.pending_key_deadline(), None);
+    }
+
+    #[test]
+    fn test_header_shows_sort_direction() {
+        let mut table = table(&["a"]);
+        let area = Rect::new(0, 0, 60, 4);
+        let mut buf = Buffer::empty(area);
+        table.render(&Config::default(), false, area, &mut buf);
+
+        let header: String = (0..area.width)
+            .map(|x| buf[(x, 1)].symbol().to_string())
+            .collect();
+        assert!(header.contains("Name "), "{header}");
+        assert!(header.contains("Modified ↓"), "{header}");
+        assert_eq!(buf[(0, 1)].bg, Config::default().colors.project_header_bg);
+    }
+
+    #[test]
+    fn test_new_projects_highlighted() {
+        let mut table = ProjectTable::new(&Config::default());
+        let add = |table: &mut ProjectTable, name| {
+            table
+                .update(ProjectEvent::Add(Box::new(project(name))))
+                .unwrap()
+        };
+
+        add(&mut table, "a");
+        assert_eq!(table.new_project_deadline(), None);
+
+        // Whether found by a rescan or the watcher once the first scan is done
+        table.update(ProjectEvent::Scanned).unwrap();
+        table.begin_rescan();
+        add(&mut table, "a");
+        add(&mut table, "b");
+        assert_eq!(
+            table.new_projects.keys().collect::<Vec<_>>(),
+            vec![&PathBuf::from("/projects/b")]
+        );
+        assert!(table.new_project_deadline().is_some());
+
+        table.fade_new_projects();
+        assert_eq!(table.new_projects.len(), 1);
+        for found in table.new_projects.values_mut() {
+            *found -= NEW_PROJECT_HIGHLIGHT;
+        }
+        table.fade_new_projects();
+        assert!(table.new_projects.is_empty());
+    }
+
+    #[test]
+    fn test_scrollbar() {
+        let names: Vec<String> = (0..20).map(|i| format!("p{:02}", i)).collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        let mut table = table(&names);
+        let area = Rect::new(0, 0, 40, 7);
+        let thumb_rows = |table: &mut ProjectTable| {
+            let mut buf = Buffer::empty(area);
+            table.render(&Config::default(), false, area, &mut buf);
+            (2..area.height)
+                .filter(|y| buf[(area.width - 1, *y)].symbol() == symbols::block::FULL)
+                .collect::<Vec<_>>()
+        };
+
+        table.next();
+        assert_eq!(thumb_rows(&mut table).first(), Some(&2));
+        table.go_bottom();
+        assert_eq!(thumb_rows(&mut table).last(), Some(&(area.height - 1)));
+
+        // Everything fits so there is nothing to scroll
+        let mut table = self::table(&["a", "b"]);
+        assert!(thumb_rows(&mut table).is_empty());
+    }
+
+    #[test]
+    fn test_kind_colors() {
+        let config: Config = toml::from_str("[colors.kinds]\nRust = \"red\"").unwrap();
+        let mut table = ProjectTable::new(&config);
+        table.items.add(project("plain"));
+        table.items.add(Project {
+            kind: Some(ProjectKind::Rust),
+            ..project("rusty")
+        });
+
+        let area = Rect::new(0, 0, 40, 4);
+        let mut buf = Buffer::empty(area);
+        table.render(&config, false, area, &mut buf);
+
+        // Rows in the order added, below the header row
+        assert_eq!(buf[(0, 2)].symbol(), "p");
+        assert_ne!(buf[(0, 2)].fg, Color::Red);
+        assert_eq!(buf[(0, 3)].symbol(), "r");
+        assert_eq!(buf[(0, 3)].fg, Color::Red);
+    }
+
+    #[test]
+    fn test_dirty_marker() {
+        let config = Config::default();
+        let mut table = ProjectTable::new(&config);
+        table.items.add(Project {
+            git: Some(GitStatus {
+                dirty: true,
+                ..Default::default()
+            }),
+            ..project("changed")
+        });
+        table.items.add(project("clean"));
+
+        let area = Rect::new(0, 0, 40, 4);
+        let mut buf = Buffer::empty(area);
+        table.render(&config, false, area, &mut buf);
+
+        let name = |y| (0..9).map(|x| buf[(x, y)].symbol()).collect::<String>();
+        assert_eq!(name(2), "changed ●");
+        assert_eq!(buf[(8, 2)].fg, config.colors.dirty_marker_fg);
+        assert_eq!(name(3), "clean    ");
+    }
+
+    #[test]
+    fn test_hidden_columns() {
+        let columns: ColumnConfig = toml::from_str("modified = false\nbranch = false").unwrap();
+
+        assert_eq!(columns.shown(false), vec![Column::Name]);
+        assert_eq!(
+            columns.shown(true),
+            vec![Column::Name, Column::FileCount, Column::Size, Column::Kind]
+        );
+        assert_eq!(
+            ColumnConfig::default().shown(false),
+            vec![Column::Name, Column::Modified]
+        );
+    }
+
+    #[test]
+    fn test_name_truncation() {
+        let name = "my-very-long-service";
+
+        assert_eq!(NameTruncation::Middle.truncate(name, 20), name);
+        assert_eq!(NameTruncation::Middle.truncate(name, 12), "my-ver…rvice");
+        assert_eq!(NameTruncation::Start.truncate(name, 12), "…ong-service");
+        assert_eq!(NameTruncation::End.truncate(name, 12), "my-very-lon…");
+        assert_eq!(NameTruncation::Middle.truncate(name, 1), "…");
+        assert_eq!(NameTruncation::Middle.truncate(name, 0), "");
+        // Wide characters count as two columns
+        assert_eq!(NameTruncation::End.truncate("プロジェクト", 6), "プロ…");
     }
 }
@@ -0,0 +1,82 @@
+use std::collections::VecDeque;
+
+use crate::project::{Project, ProjectKey};
+
+/// Number of readmes kept, enough for moving back and forth over nearby projects
+const CAPACITY: usize = 8;
+
+/// Recently shown readmes for `lazy_readme`, read from disk the first time a project is shown
+#[derive(Default)]
+pub(crate) struct ReadmeCache {
+    /// Least recently used first
+    entries: VecDeque<(ProjectKey, Option<String>)>,
+}
+
+impl ReadmeCache {
+    pub(crate) fn get(&mut self, project: &Project) -> Option<&str> {
+        let key = project.key();
+
+        let entry = match self.entries.iter().position(|(k, _)| k == key) {
+            Some(idx) => self.entries.remove(idx),
+            None => None,
+        }
+        .unwrap_or_else(|| {
+            let readme = project
+                .readme_path
+                .as_ref()
+                .and_then(|path| std::fs::read_to_string(path).ok());
+            (key.clone(), readme)
+        });
+
+        if self.entries.len() >= CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+
+        self.entries
+            .back()
+            .and_then(|(_, readme)| readme.as_deref())
+    }
+
+    /// Forget every readme so edited files are read again
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    use super::*;
+    use crate::project::test_project;
+
+    fn project(path: &str, readme_path: Option<&str>) -> Project {
+        Project {
+            readme_path: readme_path.map(PathBuf::from),
+            ..test_project(path, SystemTime::UNIX_EPOCH)
+        }
+    }
+
+    #[test]
+    fn test_readme_cache() {
+        let mut cache = ReadmeCache::default();
+        let with_readme = project(
+            "tests/test_projects/test_project_a",
+            Some("tests/test_projects/test_project_a/README.md"),
+        );
+
+        assert!(cache.get(&with_readme).is_some());
+        assert_eq!(cache.get(&project("/none", None)), None);
+
+        for i in 0..CAPACITY * 2 {
+            cache.get(&project(&format!("/other/{i}"), None));
+        }
+        assert_eq!(cache.entries.len(), CAPACITY);
+        assert!(!cache
+            .entries
+            .iter()
+            .any(|(key, _)| key == with_readme.key()));
+    }
+}